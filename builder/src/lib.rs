@@ -1,3 +1,6 @@
+use quote::ToTokens;
+use syn::ext::IdentExt;
+
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = syn::parse_macro_input!(input as syn::DeriveInput);
@@ -14,25 +17,97 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         unimplemented!()
     };
 
+    let struct_attrs = match parse_struct_attrs(&ast.attrs) {
+        Ok(struct_attrs) => struct_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let fields = match fields
+        .iter()
+        .map(BuilderField::new)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let builder_ident = quote::format_ident!("{}Builder", &ident);
 
-    let builder_fields_definition = generate_builder_fields_definition(&fields);
+    let core = struct_attrs.core();
+    let alloc = struct_attrs.alloc();
+
+    let builder_fields_definition = generate_builder_fields_definition(&fields, &struct_attrs);
     let builder_fields_init = generate_builder_fields_init(&fields);
-    let builder_methods = generate_builder_methods(&fields);
+    let builder_fields_from_value = generate_builder_fields_from_value(&fields);
+    let preset_constructors = generate_preset_constructors(&builder_ident, &struct_attrs);
+    let builder_methods = generate_builder_methods(&fields, &struct_attrs);
     let build_fn_definition = generate_build_fn_definition(&fields);
-    let attr_methods = generate_attr_methods(&fields);
+    let attr_methods = generate_attr_methods(&fields, &struct_attrs);
+    let introspection_methods = generate_introspection_methods(&fields, &struct_attrs);
+    let required_fields = generate_required_fields_check(&fields);
+
+    let builder_doc = match &struct_attrs.doc {
+        Some(doc) => doc.value(),
+        None => format!("Builder for [`{}`].", ident),
+    };
+    let builder_fn_doc = format!("Creates a [`{}`] with no fields set.", builder_ident);
+    let builder_from_doc = format!(
+        "Creates a [`{}`] with every field set from an existing value.",
+        builder_ident
+    );
+    let builder_from_defaults_doc = format!(
+        "Creates a [`{}`] with every field set from `{}::default()`.",
+        builder_ident, ident
+    );
+    let build_fn_doc = generate_build_fn_doc(&ident, &fields);
+    let build_async_fn = struct_attrs.async_validate.as_ref().map(|validate| {
+        let doc = format!(
+            "Builds a new [`{}`] like [`build`](Self::build), then awaits `{}` on the result.",
+            ident,
+            validate.to_token_stream().to_string().replace(' ', ""),
+        );
+        quote::quote! {
+            #[doc = #doc]
+            pub async fn build_async(&mut self) -> #core::result::Result<#ident, #alloc::boxed::Box<dyn #core::error::Error>> {
+                let value = self.build()?;
+                #validate(&value).await?;
+                Ok(value)
+            }
+        }
+    });
+    let debug_impls = generate_debug_impls(&ident, &builder_ident, &fields, &struct_attrs);
 
     let generated = quote::quote! {
+        #[doc = #builder_doc]
         pub struct #builder_ident {
             #(#builder_fields_definition,)*
         }
 
         impl #ident {
+            #[doc = #builder_fn_doc]
             pub fn builder() -> #builder_ident {
                 #builder_ident {
                     #(#builder_fields_init,)*
                 }
             }
+
+            #[doc = #builder_from_doc]
+            pub fn builder_from(value: #ident) -> #builder_ident {
+                #builder_ident {
+                    #(#builder_fields_from_value,)*
+                }
+            }
+
+            #[doc = #builder_from_defaults_doc]
+            // The higher-ranked bound keeps the compiler from rejecting this method outright when
+            // the struct does not implement Default; it simply becomes uncallable instead.
+            pub fn builder_from_defaults() -> #builder_ident
+            where
+                for<'a> #ident: #core::default::Default,
+            {
+                Self::builder_from(#core::default::Default::default())
+            }
+
+            #(#preset_constructors)*
         }
 
         impl #builder_ident {
@@ -40,17 +115,323 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
             #(#attr_methods)*
 
-            pub fn build(&mut self) -> std::result::Result<#ident, std::boxed::Box<dyn std::error::Error>> {
+            #(#introspection_methods)*
+
+            /// Returns the names of the required fields that have not been set yet.
+            pub fn missing_fields(&self) -> #alloc::vec::Vec<&'static str> {
+                let mut missing = #alloc::vec::Vec::new();
+                #(#required_fields)*
+                missing
+            }
+
+            #[doc = #build_fn_doc]
+            pub fn build(&mut self) -> #core::result::Result<#ident, #alloc::boxed::Box<dyn #core::error::Error>> {
                 Ok(#ident {
                     #(#build_fn_definition,)*
                 })
             }
+
+            #build_async_fn
         }
+
+        #debug_impls
     };
 
     proc_macro::TokenStream::from(generated)
 }
 
+/// A named field of the input struct together with its parsed `#[builder(...)]` attributes.
+struct BuilderField<'a> {
+    field: &'a syn::Field,
+    ident: &'a syn::Ident,
+    attrs: FieldAttrs,
+}
+
+impl<'a> BuilderField<'a> {
+    fn new(field: &'a syn::Field) -> syn::Result<Self> {
+        Ok(BuilderField {
+            field,
+            ident: field.ident.as_ref().expect("Expected struct field"),
+            attrs: parse_field_attrs(field)?,
+        })
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    /// `each = "..."`: name of the one-element-at-a-time method.
+    each: Option<syn::Ident>,
+    /// `setter(name = "...")`: overrides the name of the all-at-once setter.
+    setter_name: Option<syn::Ident>,
+    /// `setter(transform = |...| ...)`: maps the setter arguments into the field value.
+    setter_transform: Option<SetterTransform>,
+    /// `sensitive`: the value is redacted from generated `Debug` output.
+    sensitive: bool,
+    /// `alias = "..."`: old setter names kept as deprecated forwarding methods.
+    aliases: Vec<syn::Ident>,
+}
+
+struct SetterTransform {
+    closure: syn::ExprClosure,
+    arg_types: Vec<syn::Type>,
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    /// `doc = "..."`: rustdoc for the generated builder type.
+    doc: Option<syn::LitStr>,
+    /// `setter(prefix = "...")`: prepended to the name of every setter without an explicit name.
+    setter_prefix: Option<String>,
+    /// `derive(Debug)`: generate a `Debug` impl for the builder.
+    derive_debug: bool,
+    /// `target_debug`: generate a `Debug` impl for the target struct as well.
+    target_debug: bool,
+    /// `no_std`: expand against `core` and `alloc` instead of `std`.
+    no_std: bool,
+    /// `preset(name = "...", expr = "...")`: named starting configurations.
+    presets: Vec<Preset>,
+    /// `build_fn(async_validate = "...")`: async validator awaited by `build_async()`.
+    async_validate: Option<syn::Path>,
+}
+
+/// A `<name>_builder()` constructor that starts from the value of `expr`.
+struct Preset {
+    name: syn::Ident,
+    expr: syn::Expr,
+}
+
+impl StructAttrs {
+    /// Root path for items that live in `core`.
+    fn core(&self) -> proc_macro2::TokenStream {
+        if self.no_std {
+            quote::quote! { core }
+        } else {
+            quote::quote! { std }
+        }
+    }
+
+    /// Root path for items that live in `alloc`. In `no_std` mode the caller needs `extern crate
+    /// alloc;`.
+    fn alloc(&self) -> proc_macro2::TokenStream {
+        if self.no_std {
+            quote::quote! { alloc }
+        } else {
+            quote::quote! { std }
+        }
+    }
+}
+
+fn parse_struct_attrs(attrs: &[syn::Attribute]) -> syn::Result<StructAttrs> {
+    let mut struct_attrs = StructAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("doc") {
+                struct_attrs.doc = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("Debug") {
+                        struct_attrs.derive_debug = true;
+                        Ok(())
+                    } else {
+                        Err(unknown_key(&meta, "derive", &["Debug"]))
+                    }
+                })
+            } else if meta.path.is_ident("target_debug") {
+                struct_attrs.target_debug = true;
+                Ok(())
+            } else if meta.path.is_ident("no_std") {
+                struct_attrs.no_std = true;
+                Ok(())
+            } else if meta.path.is_ident("build_fn") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("async_validate") {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        struct_attrs.async_validate = Some(literal.parse()?);
+                        Ok(())
+                    } else {
+                        Err(unknown_key(&meta, "build_fn", &["async_validate"]))
+                    }
+                })
+            } else if meta.path.is_ident("preset") {
+                let mut name = None;
+                let mut expr = None;
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        name = Some(literal.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("expr") {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        expr = Some(literal.parse()?);
+                        Ok(())
+                    } else {
+                        Err(unknown_key(&meta, "preset", &["name", "expr"]))
+                    }
+                })?;
+                match (name, expr) {
+                    (Some(name), Some(expr)) => {
+                        struct_attrs.presets.push(Preset { name, expr });
+                        Ok(())
+                    }
+                    _ => Err(meta.error(r#"expected `preset(name = "...", expr = "...")`"#)),
+                }
+            } else if meta.path.is_ident("setter") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("prefix") {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        struct_attrs.setter_prefix = Some(literal.value());
+                        Ok(())
+                    } else {
+                        Err(unknown_key(&meta, "setter", &["prefix"]))
+                    }
+                })
+            } else {
+                Err(unknown_key(
+                    &meta,
+                    "builder",
+                    &[
+                        "doc",
+                        "setter",
+                        "derive",
+                        "target_debug",
+                        "no_std",
+                        "build_fn",
+                        "preset",
+                    ],
+                ))
+            }
+        })?;
+    }
+    Ok(struct_attrs)
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut field_attrs = FieldAttrs::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("builder"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                let literal: syn::LitStr = meta.value()?.parse()?;
+                field_attrs.each = Some(literal.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("sensitive") {
+                field_attrs.sensitive = true;
+                Ok(())
+            } else if meta.path.is_ident("alias") {
+                let literal: syn::LitStr = meta.value()?.parse()?;
+                field_attrs.aliases.push(literal.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("setter") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        field_attrs.setter_name = Some(literal.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("transform") {
+                        let closure: syn::ExprClosure = meta.value()?.parse()?;
+                        field_attrs.setter_transform = Some(parse_setter_transform(closure)?);
+                        Ok(())
+                    } else {
+                        Err(unknown_key(&meta, "setter", &["name", "transform"]))
+                    }
+                })
+            } else {
+                Err(unknown_key(
+                    &meta,
+                    "builder",
+                    &["each", "setter", "sensitive", "alias"],
+                ))
+            }
+        })?;
+    }
+    Ok(field_attrs)
+}
+
+/// Error for a key that is not valid inside `list(...)`, listing the valid keys and suggesting the
+/// closest one if it looks like a typo.
+fn unknown_key(meta: &syn::meta::ParseNestedMeta, list: &str, valid_keys: &[&str]) -> syn::Error {
+    let key = meta.path.to_token_stream().to_string().replace(' ', "");
+    let suggestion = valid_keys
+        .iter()
+        .map(|valid_key| (edit_distance(&key, valid_key), valid_key))
+        .filter(|(distance, valid_key)| *distance <= std::cmp::max(1, valid_key.len() / 3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, valid_key)| format!(", did you mean `{}`?", valid_key))
+        .unwrap_or_default();
+    let valid_keys = valid_keys
+        .iter()
+        .map(|valid_key| format!("`{}`", valid_key))
+        .collect::<Vec<_>>()
+        .join(", ");
+    meta.error(format!(
+        "unknown key `{}` in `{}(...)`{} valid keys are: {}",
+        key,
+        list,
+        if suggestion.is_empty() {
+            ","
+        } else {
+            &suggestion
+        },
+        valid_keys
+    ))
+}
+
+/// Edit distance between two strings, counting a swap of two adjacent characters as one edit
+/// (optimal string alignment), since that is the most common typo in short keys.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before_previous: Vec<usize> = vec![];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 0..a.len() {
+        let mut current = vec![i + 1];
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current.push(distance);
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+fn parse_setter_transform(closure: syn::ExprClosure) -> syn::Result<SetterTransform> {
+    let arg_types = closure
+        .inputs
+        .iter()
+        .map(|input| {
+            if let syn::Pat::Type(syn::PatType { ty, .. }) = input {
+                Ok((**ty).clone())
+            } else {
+                Err(syn::Error::new_spanned(
+                    input,
+                    "setter transform arguments need an explicit type",
+                ))
+            }
+        })
+        .collect::<syn::Result<_>>()?;
+    Ok(SetterTransform { closure, arg_types })
+}
+
+/// The name of the all-at-once setter for a field, taking `setter(name)` and `setter(prefix)` into
+/// account.
+fn setter_ident(field: &BuilderField, struct_attrs: &StructAttrs) -> syn::Ident {
+    if let Some(name) = &field.attrs.setter_name {
+        name.clone()
+    } else if let Some(prefix) = &struct_attrs.setter_prefix {
+        quote::format_ident!("{}{}", prefix, field.ident.unraw())
+    } else {
+        field.ident.clone()
+    }
+}
+
 fn is_option(field: &syn::Field) -> (bool, Option<syn::PathSegment>) {
     if let syn::Type::Path(syn::TypePath {
         qself: None,
@@ -68,65 +449,231 @@ fn is_option(field: &syn::Field) -> (bool, Option<syn::PathSegment>) {
     }
 }
 
-fn generate_builder_fields_definition(
-    original_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
-    original_fields.iter().map(|field| {
-        let ident = &field.ident;
-        let ty = &field.ty;
-        if let (true, _) = is_option(field) {
+fn generate_builder_fields_definition<'a>(
+    original_fields: &'a [BuilderField<'a>],
+    struct_attrs: &StructAttrs,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    let core = struct_attrs.core();
+    original_fields.iter().map(move |field| {
+        let ident = field.ident;
+        let ty = &field.field.ty;
+        if let (true, _) = is_option(field.field) {
             quote::quote! {
-                #ident: std::option::#ty
+                #ident: #core::option::#ty
             }
         } else {
             quote::quote! {
-                #ident: std::option::Option<#ty>
+                #ident: #core::option::Option<#ty>
             }
         }
     })
 }
 
-fn generate_builder_fields_init(
-    original_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+fn generate_builder_fields_init<'a>(
+    original_fields: &'a [BuilderField<'a>],
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
     original_fields.iter().map(|field| {
-        let ident = &field.ident;
+        let ident = field.ident;
         quote::quote! {
             #ident: None
         }
     })
 }
 
-fn generate_builder_methods(
-    original_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+fn generate_builder_fields_from_value<'a>(
+    original_fields: &'a [BuilderField<'a>],
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
     original_fields.iter().map(|field| {
-        let ident = &field.ident;
-        let ty = &field.ty;
-
-        let arg = if let (true, Some(segment)) = is_option(field) {
-            angle_bracketed_inner_type_from_segment(&segment).unwrap_or(quote::quote! { #ty})
+        let ident = field.ident;
+        if let (true, _) = is_option(field.field) {
+            quote::quote! {
+                #ident: value.#ident
+            }
         } else {
             quote::quote! {
-                #ty
+                #ident: Some(value.#ident)
+            }
+        }
+    })
+}
+
+fn generate_preset_constructors<'a>(
+    builder_ident: &'a syn::Ident,
+    struct_attrs: &'a StructAttrs,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    struct_attrs
+        .presets
+        .iter()
+        .map(move |Preset { name, expr }| {
+            let fn_ident = quote::format_ident!("{}_builder", name);
+            let doc = format!("Creates a builder pre-filled with the `{}` preset.", name);
+            quote::quote! {
+                #[doc = #doc]
+                pub fn #fn_ident() -> #builder_ident {
+                    Self::builder_from(#expr)
+                }
             }
+        })
+}
+
+fn generate_builder_methods<'a>(
+    original_fields: &'a [BuilderField<'a>],
+    struct_attrs: &'a StructAttrs,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    original_fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = &field.field.ty;
+        let setter_ident = setter_ident(field, struct_attrs);
+        let doc = field_doc_or(field, format!("Sets the `{}` field.", ident.unraw()));
+
+        let (arg_idents, arg_types, value) = if let Some(SetterTransform { closure, arg_types }) =
+            &field.attrs.setter_transform
+        {
+            let arg_idents: Vec<_> = (0..arg_types.len())
+                .map(|i| quote::format_ident!("arg{}", i))
+                .collect();
+            let arg_types = arg_types.iter().map(|ty| quote::quote! { #ty }).collect();
+            let value = quote::quote! { (#closure)(#(#arg_idents),*) };
+            (arg_idents, arg_types, value)
+        } else {
+            let arg = if let (true, Some(segment)) = is_option(field.field) {
+                angle_bracketed_inner_type_from_segment(&segment).unwrap_or(quote::quote! { #ty})
+            } else {
+                quote::quote! {
+                    #ty
+                }
+            };
+            (vec![ident.clone()], vec![arg], quote::quote! { #ident })
         };
 
+        let aliases = field.attrs.aliases.iter().map(|alias| {
+            let note = format!("renamed to `{}`", setter_ident);
+            let alias_doc = format!(
+                "Deprecated alias for [`{}`](Self::{}).",
+                setter_ident, setter_ident
+            );
+            quote::quote! {
+                #[doc = #alias_doc]
+                #[deprecated(note = #note)]
+                pub fn #alias(&mut self, #(#arg_idents: #arg_types),*) -> &mut Self {
+                    self.#setter_ident(#(#arg_idents),*)
+                }
+            }
+        });
+
         quote::quote! {
-            fn #ident(&mut self, #ident: #arg) -> &mut Self {
-                self.#ident = Some(#ident);
+            #(#doc)*
+            pub fn #setter_ident(&mut self, #(#arg_idents: #arg_types),*) -> &mut Self {
+                self.#ident = Some(#value);
                 self
             }
+
+            #(#aliases)*
         }
     })
 }
 
-fn generate_build_fn_definition(
-    original_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+/// The field's own `///` doc comments, or a single generated line if it has none.
+fn field_doc_or(field: &BuilderField, fallback: String) -> Vec<proc_macro2::TokenStream> {
+    let docs: Vec<_> = field
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .map(|attr| attr.to_token_stream())
+        .collect();
+    if docs.is_empty() && !fallback.is_empty() {
+        vec![quote::quote! { #[doc = #fallback] }]
+    } else {
+        docs
+    }
+}
+
+fn generate_build_fn_doc(ident: &syn::Ident, original_fields: &[BuilderField]) -> String {
+    let required: Vec<_> = original_fields
+        .iter()
+        .filter(|field| is_required(field))
+        .map(|field| format!("`{}`", field.ident.unraw()))
+        .collect();
+    let mut doc = format!("Builds a new [`{}`] from the values set so far.\n\n", ident);
+    if required.is_empty() {
+        doc.push_str("There are no required fields.");
+    } else {
+        doc.push_str(&format!(
+            "Required fields: {}. Required fields that were never set fall back to \
+             `Default::default()`.",
+            required.join(", ")
+        ));
+    }
+    doc.push_str(
+        " Optional fields default to `None` and fields populated through `each` methods default \
+         to an empty collection.",
+    );
+    doc
+}
+
+fn is_required(field: &BuilderField) -> bool {
+    let (is_option, _) = is_option(field.field);
+    !is_option && field.attrs.each.is_none()
+}
+
+fn generate_introspection_methods<'a>(
+    original_fields: &'a [BuilderField<'a>],
+    struct_attrs: &StructAttrs,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    let core = struct_attrs.core();
+    original_fields.iter().map(move |field| {
+        let ident = field.ident;
+        let ty = &field.field.ty;
+        let is_set_ident = quote::format_ident!("is_{}_set", ident.unraw());
+        let get_ident = quote::format_ident!("get_{}", ident.unraw());
+
+        let inner = if let (true, Some(segment)) = is_option(field.field) {
+            angle_bracketed_inner_type_from_segment(&segment).unwrap_or(quote::quote! { #ty })
+        } else {
+            quote::quote! { #ty }
+        };
+
+        let is_set_doc = format!("Returns whether `{}` has been set.", ident.unraw());
+        let get_doc = format!("Returns the value of `{}`, if set.", ident.unraw());
+
+        quote::quote! {
+            #[doc = #is_set_doc]
+            pub fn #is_set_ident(&self) -> bool {
+                self.#ident.is_some()
+            }
+
+            #[doc = #get_doc]
+            pub fn #get_ident(&self) -> #core::option::Option<&#inner> {
+                self.#ident.as_ref()
+            }
+        }
+    })
+}
+
+fn generate_required_fields_check<'a>(
+    original_fields: &'a [BuilderField<'a>],
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    original_fields
+        .iter()
+        .filter(|field| is_required(field))
+        .map(|field| {
+            let ident = field.ident;
+            let name = format!("{}", ident.unraw());
+            quote::quote! {
+                if self.#ident.is_none() {
+                    missing.push(#name);
+                }
+            }
+        })
+}
+
+fn generate_build_fn_definition<'a>(
+    original_fields: &'a [BuilderField<'a>],
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
     original_fields.iter().map(|field| {
-        let ident = &field.ident;
-        if let (true, _) = is_option(field) {
+        let ident = field.ident;
+        if let (true, _) = is_option(field.field) {
             quote::quote! {
                 #ident: self.#ident.clone()
             }
@@ -138,66 +685,49 @@ fn generate_build_fn_definition(
     })
 }
 
-fn generate_attr_methods(
-    original_fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
-) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
-    original_fields.iter().map(|field| {
-        let field_ident = field.ident.as_ref().expect("Expected struct field");
+fn generate_attr_methods<'a>(
+    original_fields: &'a [BuilderField<'a>],
+    struct_attrs: &'a StructAttrs,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    let alloc = struct_attrs.alloc();
+    original_fields.iter().map(move |field| {
+        let field_ident = field.ident;
 
-        if let Some(attr) = field
-            .attrs
-            .iter()
-            .find(|attr| attr.path().is_ident("builder"))
-        {
-            let parsed: syn::MetaNameValue = attr
-                .parse_args()
-                .expect("failed parsing as name-value expr");
-
-            if parsed.path.get_ident().unwrap() != "each" {
-                use syn::spanned::Spanned;
-                syn::Error::new(parsed.path.span(), r#"expected `builder(each = "...")`"#)
-                    .to_compile_error()
-            } else if let syn::Expr::Lit(syn::ExprLit {
-                lit: syn::Lit::Str(literal),
-                ..
-            }) = parsed.value
-            {
-                let new_fn_ident = literal.value();
-                if *field_ident == new_fn_ident {
-                    quote::quote! {}
+        if let Some(new_fn_ident) = &field.attrs.each {
+            if *new_fn_ident == setter_ident(field, struct_attrs) {
+                quote::quote! {}
+            } else {
+                let inner_type = if let syn::Type::Path(syn::TypePath {
+                    qself: None,
+                    path: syn::Path { segments, .. },
+                }) = &field.field.ty
+                {
+                    if let Some(segment) = segments.iter().find(|segment| segment.ident == "Vec") {
+                        angle_bracketed_inner_type_from_segment(segment)
+                            .expect("Expected inner type for Vec<>")
+                    } else {
+                        panic!("This attribute is only applicable to vectors");
+                    }
                 } else {
-                    let inner_type = if let syn::Type::Path(syn::TypePath {
-                        qself: None,
-                        path: syn::Path { segments, .. },
-                    }) = &field.ty
-                    {
-                        if let Some(segment) =
-                            segments.iter().find(|segment| segment.ident == "Vec")
-                        {
-                            angle_bracketed_inner_type_from_segment(segment)
-                                .expect("Expected inner type for Vec<>")
+                    unimplemented!();
+                };
+
+                let doc = format!("Appends a single element to `{}`.", field_ident.unraw());
+                let field_doc = field_doc_or(field, String::new());
+                let separator = (!field_doc.is_empty()).then(|| quote::quote! { #[doc = ""] });
+                quote::quote! {
+                    #[doc = #doc]
+                    #separator
+                    #(#field_doc)*
+                    pub fn #new_fn_ident(&mut self, #new_fn_ident: #inner_type) -> &mut Self {
+                        if let Some(x) = self.#field_ident.as_mut() {
+                            x.push(#new_fn_ident);
                         } else {
-                            panic!("This attribute is only applicable to vectors");
-                        }
-                    } else {
-                        unimplemented!();
-                    };
-
-                    // neat! we can parse a string directly into a tokenstream
-                    let new_fn_ident: proc_macro2::TokenStream = new_fn_ident.parse().unwrap();
-                    quote::quote! {
-                        fn #new_fn_ident(&mut self, #new_fn_ident: #inner_type) -> &mut Self {
-                            if let Some(x) = self.#field_ident.as_mut() {
-                                x.push(#new_fn_ident);
-                            } else {
-                                self.#field_ident = Some(vec![#new_fn_ident]);
-                            }
-                            self
+                            self.#field_ident = Some(#alloc::vec![#new_fn_ident]);
                         }
+                        self
                     }
                 }
-            } else {
-                unimplemented!("Expected value to be string literal")
             }
         } else {
             quote::quote! {}
@@ -205,6 +735,46 @@ fn generate_attr_methods(
     })
 }
 
+/// `Debug` impls for the builder and, if requested, the target struct. Fields marked
+/// `#[builder(sensitive)]` print as `"<redacted>"` in both.
+fn generate_debug_impls(
+    ident: &syn::Ident,
+    builder_ident: &syn::Ident,
+    original_fields: &[BuilderField],
+    struct_attrs: &StructAttrs,
+) -> proc_macro2::TokenStream {
+    let debug_fields = original_fields.iter().map(|field| {
+        let ident = field.ident;
+        let name = format!("{}", ident.unraw());
+        if field.attrs.sensitive {
+            quote::quote! { .field(#name, &"<redacted>") }
+        } else {
+            quote::quote! { .field(#name, &self.#ident) }
+        }
+    });
+    let core = struct_attrs.core();
+    let debug_impl = |ty: &syn::Ident| {
+        let name = format!("{}", ty);
+        let debug_fields = debug_fields.clone();
+        quote::quote! {
+            impl #core::fmt::Debug for #ty {
+                fn fmt(&self, fmt: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                    fmt.debug_struct(#name)
+                    #(#debug_fields)*
+                    .finish()
+                }
+            }
+        }
+    };
+
+    let builder_debug = struct_attrs.derive_debug.then(|| debug_impl(builder_ident));
+    let target_debug = struct_attrs.target_debug.then(|| debug_impl(ident));
+    quote::quote! {
+        #builder_debug
+        #target_debug
+    }
+}
+
 fn angle_bracketed_inner_type_from_segment(
     segment: &syn::PathSegment,
 ) -> Option<proc_macro2::TokenStream> {
@@ -0,0 +1,50 @@
+// Interactive tools and config UIs want to show which fields of a builder have
+// been filled in before calling build().
+//
+// Generate a pair of accessors for every field, plus a summary of the required
+// fields that have not been set yet. A field is required unless its type is
+// Option or it is populated one element at a time through
+// #[builder(each = "...")].
+//
+//     impl CommandBuilder {
+//         pub fn is_executable_set(&self) -> bool {...}
+//         pub fn get_executable(&self) -> Option<&String> {...}
+//
+//         ...
+//
+//         pub fn missing_fields(&self) -> Vec<&'static str> {...}
+//     }
+//
+// Getters for optional fields return the inner type, so `current_dir:
+// Option<String>` produces `fn get_current_dir(&self) -> Option<&String>`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert!(!builder.is_executable_set());
+    assert_eq!(builder.get_executable(), None);
+    assert_eq!(builder.missing_fields(), vec!["executable", "env"]);
+
+    builder.executable("cargo".to_owned());
+    assert!(builder.is_executable_set());
+    assert_eq!(builder.get_executable().map(String::as_str), Some("cargo"));
+    assert_eq!(builder.missing_fields(), vec!["env"]);
+
+    builder.arg("build".to_owned()).current_dir("..".to_owned());
+    assert!(builder.is_args_set());
+    assert_eq!(builder.get_args().map(Vec::len), Some(1));
+    assert_eq!(builder.get_current_dir().map(String::as_str), Some(".."));
+
+    builder.env(vec![]);
+    assert!(builder.missing_fields().is_empty());
+}
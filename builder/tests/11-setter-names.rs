@@ -0,0 +1,51 @@
+// Generated setters are named after their field, which does not work for
+// fields that clash with other builder methods (such as `build`) and reads
+// awkwardly for fields named after keywords.
+//
+// Accept a struct-level #[builder(setter(prefix = "..."))] that is prepended
+// to every setter name, and a field-level #[builder(setter(name = "..."))]
+// that replaces the setter name for that one field. An explicit name wins over
+// the prefix.
+//
+// A field-level #[builder(setter(transform = |...| ...))] lets a setter accept
+// different arguments than the field type. The closure arguments need explicit
+// types; they become the setter's parameters and the closure's return value is
+// stored in the field.
+//
+//     fn with_build(&mut self, arg0: &str) -> &mut Self {
+//         self.build = Some((|s: &str| s.trim().to_owned())(arg0));
+//         self
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(setter(prefix = "with_"))]
+pub struct Command {
+    #[builder(setter(name = "exe"))]
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(transform = |key: &str, value: &str| vec![format!("{}={}", key, value)]))]
+    env: Vec<String>,
+    #[builder(setter(transform = |s: &str| s.trim().to_owned()))]
+    build: String,
+    r#type: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .exe("cargo".to_owned())
+        .arg("build".to_owned())
+        .with_env("RUST_LOG", "debug")
+        .with_build("  release ")
+        .with_type("bin".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.build, "release");
+    assert_eq!(command.r#type.as_deref(), Some("bin"));
+}
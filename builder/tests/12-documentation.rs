@@ -0,0 +1,42 @@
+// The builder is part of the public API of whichever crate derives it, so it
+// should show up in rustdoc as well as the struct it builds.
+//
+// Copy each field's doc comments onto its setter and its `each` method, and
+// generate documentation for the remaining items: the builder type, the
+// `builder()` constructor and `build()`, which lists the required fields and
+// what happens to fields that were never set. A struct-level
+// #[builder(doc = "...")] replaces the generated documentation of the builder
+// type.
+//
+// This test denies missing_docs, so it fails to compile if any public item
+// generated by the macro is undocumented.
+
+#![deny(missing_docs)]
+
+//! Documentation checks for the generated builder.
+
+use derive_builder::Builder;
+
+/// A process to spawn.
+#[derive(Builder)]
+#[builder(doc = "Incrementally configures a [`Command`] before spawning it.")]
+pub struct Command {
+    /// Path of the program to run.
+    pub executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    /// Working directory, inherited from the parent when unset.
+    pub current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("doc".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["doc"]);
+}
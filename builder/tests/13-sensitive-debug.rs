@@ -0,0 +1,39 @@
+// Builders for credentials end up in error logs and panic messages, so it must
+// be possible to give them a Debug impl without leaking secrets.
+//
+// A struct-level #[builder(derive(Debug))] generates a Debug impl for the
+// builder. Fields marked #[builder(sensitive)] print as "<redacted>" instead of
+// their value. Adding #[builder(target_debug)] generates the same kind of impl
+// for the target struct, so it does not need a plain #[derive(Debug)] that
+// would print the secret.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Debug), target_debug)]
+pub struct Credentials {
+    username: String,
+    #[builder(sensitive)]
+    password: String,
+    api_token: Option<String>,
+}
+
+fn main() {
+    let mut builder = Credentials::builder();
+    builder.username("admin".to_owned());
+    builder.password("hunter2".to_owned());
+
+    let debug = format!("{:?}", builder);
+    assert_eq!(
+        debug,
+        r#"CredentialsBuilder { username: Some("admin"), password: "<redacted>", api_token: None }"#,
+    );
+
+    let credentials = builder.build().unwrap();
+    let debug = format!("{:?}", credentials);
+    assert_eq!(
+        debug,
+        r#"Credentials { username: "admin", password: "<redacted>", api_token: None }"#,
+    );
+    assert!(!debug.contains("hunter2"));
+}
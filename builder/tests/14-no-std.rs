@@ -0,0 +1,44 @@
+// Firmware crates are #![no_std] and only have `core` and `alloc` available.
+// A struct-level #[builder(no_std)] makes the generated code refer to
+// core::option::Option, alloc::boxed::Box, core::error::Error, alloc::vec! and
+// so on instead of their std re-exports. The deriving crate needs to declare
+// `extern crate alloc;`.
+//
+// A test binary cannot easily be no_std itself, so instead the derive is
+// invoked inside a module that shadows `std` with an empty module. Any path
+// the macro emits through `std` then fails to resolve.
+
+extern crate alloc;
+
+mod firmware {
+    #![allow(dead_code)]
+
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use derive_builder::Builder;
+
+    mod std {}
+
+    #[derive(Builder)]
+    #[builder(no_std, derive(Debug))]
+    pub struct DeviceConfig {
+        pub name: String,
+        #[builder(each = "channel")]
+        pub channels: Vec<u8>,
+        pub baud_rate: Option<u32>,
+    }
+}
+
+fn main() {
+    let config = firmware::DeviceConfig::builder()
+        .name("uart0".to_owned())
+        .channel(1)
+        .channel(2)
+        .baud_rate(115_200)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "uart0");
+    assert_eq!(config.channels, vec![1, 2]);
+    assert_eq!(config.baud_rate, Some(115_200));
+}
@@ -0,0 +1,39 @@
+// Renaming a field renames its setter, which breaks every caller at once. A
+// field-level #[builder(alias = "old_name")] keeps the old setter around as a
+// deprecated method that forwards to the new one, so callers can migrate over a
+// release cycle. The attribute may be repeated to keep several old names.
+//
+//     #[deprecated(note = "renamed to `executable`")]
+//     pub fn exe(&mut self, executable: String) -> &mut Self {
+//         self.executable(executable)
+//     }
+//
+// Aliases take the same arguments as the setter they forward to, including
+// setters with a custom name or a transform.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(alias = "exe", alias = "program")]
+    executable: String,
+    #[builder(setter(name = "dir", transform = |s: &str| s.to_owned()), alias = "cwd")]
+    current_dir: Option<String>,
+}
+
+#[allow(deprecated)]
+fn main() {
+    let command = Command::builder()
+        .exe("cargo".to_owned())
+        .cwd("..")
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let command = Command::builder()
+        .program("rustc".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "rustc");
+}
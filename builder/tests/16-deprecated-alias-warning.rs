@@ -0,0 +1,16 @@
+// Callers of an alias setter should get a deprecation warning that names the
+// replacement. This is a compile_fail test that denies deprecated items.
+
+#![deny(deprecated)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(alias = "exe")]
+    executable: String,
+}
+
+fn main() {
+    let _ = Command::builder().exe("cargo".to_owned()).build();
+}
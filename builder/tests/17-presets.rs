@@ -0,0 +1,59 @@
+// Many types have a few canonical starting configurations. Rather than
+// hand-writing helpers that call the generated setters, let the builder start
+// from an existing value.
+//
+//     impl Command {
+//         pub fn builder_from(value: Command) -> CommandBuilder {...}
+//         pub fn builder_from_defaults() -> CommandBuilder {...}
+//     }
+//
+// builder_from_defaults() is only callable if the struct implements Default,
+// but its presence must not break structs that don't.
+//
+// A struct-level #[builder(preset(name = "...", expr = "..."))] generates a
+// `<name>_builder()` constructor that starts from the value of the given
+// expression. The attribute may be repeated.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Default)]
+#[builder(preset(
+    name = "release",
+    expr = r#"Command { args: vec!["--release".to_owned()], ..Command::default() }"#
+))]
+#[builder(preset(name = "nightly", expr = r#"Command { toolchain: Some("nightly".to_owned()), ..Command::default() }"#))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    toolchain: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct NoDefault {
+    value: String,
+}
+
+fn main() {
+    let command = Command::release_builder()
+        .executable("cargo".to_owned())
+        .arg("--locked".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["--release", "--locked"]);
+    assert!(command.toolchain.is_none());
+
+    let mut builder = Command::nightly_builder();
+    assert_eq!(builder.get_toolchain().map(String::as_str), Some("nightly"));
+    assert!(builder.missing_fields().is_empty());
+    let command = builder.build().unwrap();
+    assert_eq!(command.toolchain.as_deref(), Some("nightly"));
+
+    let existing = NoDefault {
+        value: "kept".to_owned(),
+    };
+    let copy = NoDefault::builder_from(existing).build().unwrap();
+    assert_eq!(copy.value, "kept");
+
+    assert!(Command::builder_from_defaults().is_executable_set());
+}
@@ -0,0 +1,66 @@
+// Some checks can only be done asynchronously, such as looking up a path
+// through a runtime's filesystem API or resolving a hostname.
+//
+// A struct-level #[builder(build_fn(async_validate = "path"))] generates an
+// additional build_async() method. It builds the value exactly like build()
+// and then awaits the validator on it:
+//
+//     async fn validate(value: &Command) -> Result<(), E>
+//
+// where E is any error that converts into Box<dyn Error>. The synchronous
+// build() is still generated. Like build(), build_async() takes &mut self so
+// it can be called at the end of a chain of setters.
+//
+// The test drives the futures with a minimal executor, since the macro must
+// not depend on any particular async runtime.
+
+use derive_builder::Builder;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[derive(Builder)]
+#[builder(build_fn(async_validate = "checks::executable_exists"))]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+}
+
+mod checks {
+    pub async fn executable_exists(command: &super::Command) -> Result<(), String> {
+        if command.executable.is_empty() {
+            Err("executable must not be empty".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let command = block_on(
+        Command::builder()
+            .executable("cargo".to_owned())
+            .args(vec!["build".to_owned()])
+            .build_async(),
+    )
+    .unwrap();
+    assert_eq!(command.executable, "cargo");
+
+    match block_on(Command::builder().build_async()) {
+        Ok(_) => panic!("expected the validator to reject an empty executable"),
+        Err(err) => assert_eq!(err.to_string(), "executable must not be empty"),
+    }
+
+    // The synchronous build() skips the validator.
+    assert!(Command::builder().build().is_ok());
+}
@@ -0,0 +1,20 @@
+// Every #[builder(...)] list, including nested ones on the struct, goes through
+// the same key validation. An unknown key reports all keys that are valid in
+// that position and, when the key looks like a typo, suggests the closest one.
+// Swapping two adjacent letters counts as a single typo.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(setter(prefx = "with_"))]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+pub struct Args {
+    #[builder(eahc = "arg")]
+    args: Vec<String>,
+}
+
+fn main() {}
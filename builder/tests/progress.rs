@@ -10,4 +10,14 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-introspection.rs");
+    t.pass("tests/11-setter-names.rs");
+    t.pass("tests/12-documentation.rs");
+    t.pass("tests/13-sensitive-debug.rs");
+    t.pass("tests/14-no-std.rs");
+    t.pass("tests/15-deprecated-alias.rs");
+    t.compile_fail("tests/16-deprecated-alias-warning.rs");
+    t.pass("tests/17-presets.rs");
+    t.pass("tests/18-async-validate.rs");
+    t.compile_fail("tests/19-unknown-key-suggestion.rs");
 }
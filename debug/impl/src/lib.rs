@@ -0,0 +1,1115 @@
+use quote::ToTokens;
+
+#[proc_macro_derive(CustomDebug, attributes(debug))]
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut parsed = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    let ident = &parsed.ident;
+    let container_attrs = match parse_container_attrs(&parsed.attrs) {
+        Ok(container_attrs) => container_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut fmt_body = match fmt_body(&parsed, &container_attrs) {
+        Ok(fmt_body) => fmt_body,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Some(max_depth) = &container_attrs.max_depth {
+        // The depth is shared by every type with a limit, so mutually recursive types count
+        // each other's levels too.
+        fmt_body = quote::quote! {
+            let __depth = ::derive_debug::__private::DepthGuard::enter();
+            if __depth.exceeds(#max_depth) {
+                return fmt.write_str("\u{2026}");
+            }
+            #fmt_body
+        };
+    }
+    let debug_fields_items = match debug_fields_items(&parsed) {
+        Ok(debug_fields_items) => debug_fields_items,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let inspect_body = if container_attrs.inspect {
+        match inspect_body(&parsed) {
+            Ok(inspect_body) => Some(inspect_body),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    } else {
+        None
+    };
+
+    let predicates = where_predicates(&parsed, &container_attrs);
+    merge_predicates(&mut parsed.generics, predicates);
+
+    let (impl_generics, ty_generics, where_clause) = parsed.generics.split_for_impl();
+    let debug_fields_impl = debug_fields_items.map(|debug_fields_items| {
+        quote::quote! {
+            impl #impl_generics ::derive_debug::__private::DebugFields for #ident #ty_generics
+            #where_clause
+            {
+                #debug_fields_items
+            }
+        }
+    });
+    let inspect_impl = inspect_body.map(|inspect_body| {
+        quote::quote! {
+            impl #impl_generics ::derive_debug::Inspect for #ident #ty_generics #where_clause {
+                fn visit_fields(
+                    &self,
+                    __visit: &mut dyn FnMut(&'static str, &dyn std::fmt::Debug),
+                ) {
+                    #inspect_body
+                }
+            }
+        }
+    });
+    let res = quote::quote! {
+        impl #impl_generics std::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #fmt_body
+            }
+        }
+
+        #debug_fields_impl
+
+        #inspect_impl
+    };
+
+    proc_macro::TokenStream::from(res)
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut parsed = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    let (fmt_body, predicates) = match display_fmt_body(&parsed) {
+        Ok(res) => res,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    merge_predicates(&mut parsed.generics, predicates);
+
+    let ident = &parsed.ident;
+    let (impl_generics, ty_generics, where_clause) = parsed.generics.split_for_impl();
+    let res = quote::quote! {
+        impl #impl_generics std::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #fmt_body
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(res)
+}
+
+/// Adds the predicates to the generics' where-clause, skipping any that are already there.
+fn merge_predicates(generics: &mut syn::Generics, predicates: Vec<syn::WherePredicate>) {
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        if !where_clause
+            .predicates
+            .iter()
+            .any(|existing| *existing == predicate)
+        {
+            where_clause.predicates.push(predicate);
+        }
+    }
+}
+
+/// The `#trait_path` bounds needed for a field of type `ty`, as far as the type parameters are
+/// concerned.
+fn inferred_predicates(
+    ty: &syn::Type,
+    type_params: &[&syn::Ident],
+    trait_path: &proc_macro2::TokenStream,
+) -> Vec<syn::WherePredicate> {
+    let mut found = vec![];
+    for t_ident in type_params {
+        contains_inner_t(ty, t_ident, &mut found);
+    }
+    found
+        .into_iter()
+        .map(|ty| syn::parse_quote!(#ty: #trait_path))
+        .collect()
+}
+
+/// The predicates the generated impl needs on top of those written on the type itself.
+///
+/// A container-level `#[debug(bound = "...")]` replaces all inference. Otherwise each field
+/// contributes either the predicates from its own `#[debug(bound = "...")]` or the `Debug` bounds
+/// inferred from its type.
+fn where_predicates(
+    input: &syn::DeriveInput,
+    container_attrs: &ContainerAttrs,
+) -> Vec<syn::WherePredicate> {
+    let mut predicates = vec![];
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect();
+
+    for field in all_fields(&input.data) {
+        // Errors have already been reported while generating the fmt body.
+        let Ok(attrs) = parse_field_attrs(field) else {
+            continue;
+        };
+
+        // Partially redacted fields are formatted through their Display impl instead.
+        if attrs.redact == Some(Redact::Partial) {
+            let ty = &field.ty;
+            predicates.push(syn::parse_quote!(#ty: std::fmt::Display));
+        }
+
+        if container_attrs.bound.is_some() {
+            continue;
+        } else if let Some(bound) = attrs.bound {
+            predicates.extend(bound);
+        } else if attrs.requires_debug() {
+            // Skipped fields and fields with a custom formatter don't need to be Debug.
+            predicates.extend(inferred_predicates(
+                &field.ty,
+                &type_params,
+                &quote::quote! { std::fmt::Debug },
+            ));
+        }
+    }
+
+    if let Some(bound) = &container_attrs.bound {
+        predicates.extend(bound.iter().cloned());
+    }
+    predicates
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    /// `#[debug(bound = "...")]`: predicates replacing all bound inference.
+    bound: Option<Vec<syn::WherePredicate>>,
+    /// `#[debug(transparent)]`: format a single-field struct as its field alone.
+    transparent: bool,
+    /// `#[debug(name = "...")]`: name printed in place of the struct's own.
+    name: Option<syn::LitStr>,
+    /// `#[debug(inspect)]`: also implement `Inspect`.
+    inspect: bool,
+    /// `#[debug(max_depth = N)]`: print `…` in place of values nested more than N levels deep.
+    max_depth: Option<syn::LitInt>,
+}
+
+fn parse_container_attrs(attrs: &[syn::Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut container_attrs = ContainerAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("debug")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                let predicates = parse_bound(meta.value()?.parse()?)?;
+                container_attrs
+                    .bound
+                    .get_or_insert_with(Vec::new)
+                    .extend(predicates);
+                Ok(())
+            } else if meta.path.is_ident("transparent") {
+                container_attrs.transparent = true;
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                container_attrs.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("inspect") {
+                container_attrs.inspect = true;
+                Ok(())
+            } else if meta.path.is_ident("max_depth") {
+                let max_depth: syn::LitInt = meta.value()?.parse()?;
+                max_depth.base10_parse::<usize>()?;
+                container_attrs.max_depth = Some(max_depth);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    r#"expected `debug(bound = "...")`, `debug(transparent)`, `debug(name = "...")`, `debug(inspect)` or `debug(max_depth = N)`"#,
+                ))
+            }
+        })?;
+    }
+    Ok(container_attrs)
+}
+
+/// Parses the comma-separated where predicates in a `bound = "..."` string.
+fn parse_bound(literal: syn::LitStr) -> syn::Result<Vec<syn::WherePredicate>> {
+    use syn::parse::Parser;
+    let predicates =
+        syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated
+            .parse_str(&literal.value())
+            .map_err(|err| syn::Error::new(literal.span(), err))?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// The body of the generated `fmt` method.
+fn fmt_body(
+    input: &syn::DeriveInput,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+            let quoted_struct_ident = match &container_attrs.name {
+                Some(name) => name.value(),
+                None => format!("{}", input.ident),
+            };
+            let packed = is_packed(&input.attrs)?;
+            let values = struct_field_values(fields, packed);
+            if container_attrs.transparent {
+                fmt_transparent(input, fields, values)
+            } else {
+                fmt_fields(&quoted_struct_ident, fields, values)
+            }
+        }
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+            if container_attrs.transparent {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`debug(transparent)` is only supported on structs",
+                ));
+            }
+            if let Some(name) = &container_attrs.name {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`debug(name = \"...\")` is only supported on structs",
+                ));
+            }
+            let arms = variants
+                .iter()
+                .map(fmt_variant_arm)
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(match_self(&arms))
+        }
+        syn::Data::Union(_) => panic!("Expected struct or enum"),
+    }
+}
+
+/// A reference to each field of `self`, in order.
+///
+/// Fields of packed structs may be unaligned, so those are copied into a temporary first. The
+/// copy goes through a helper that reports fields which are not `Copy` at their type.
+fn struct_field_values(fields: &syn::Fields, packed: bool) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(field_ident) => field_ident.to_token_stream(),
+                None => syn::Index::from(i).to_token_stream(),
+            };
+            if packed {
+                let ty = &field.ty;
+                quote::quote_spanned! {syn::spanned::Spanned::span(ty)=>
+                    &::derive_debug::__private::packed_field::<#ty>(self.#member)
+                }
+            } else {
+                quote::quote! { &self.#member }
+            }
+        })
+        .collect()
+}
+
+/// Whether the type has a `#[repr(packed)]` or `#[repr(packed(N))]` attribute.
+fn is_packed(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut packed = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let reprs = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        )?;
+        packed |= reprs.iter().any(|repr| repr.path().is_ident("packed"));
+    }
+    Ok(packed)
+}
+
+/// Formats a single-field wrapper as its inner value alone.
+fn fmt_transparent(
+    input: &syn::DeriveInput,
+    fields: &syn::Fields,
+    values: Vec<proc_macro2::TokenStream>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut formatted = vec![];
+    for (field, value) in fields.iter().zip(values) {
+        let attrs = parse_field_attrs(field)?;
+        if let Some(skip_if) = &attrs.skip_if {
+            return Err(syn::Error::new_spanned(
+                skip_if,
+                "`debug(skip_if = \"...\")` is not supported with `debug(transparent)`",
+            ));
+        }
+        if !attrs.skip {
+            formatted.push(fmt_field_value(&attrs, value));
+        }
+    }
+    match formatted.as_slice() {
+        [value] => Ok(quote::quote! { std::fmt::Debug::fmt(#value, fmt) }),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "`debug(transparent)` requires exactly one field that is not skipped",
+        )),
+    }
+}
+
+/// The items of the hidden `DebugFields` impl given to structs with named fields, which adds
+/// their fields to another type's `DebugStruct` when it uses `#[debug(flatten)]` on them.
+fn debug_fields_items(input: &syn::DeriveInput) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: fields @ syn::Fields::Named(_),
+            ..
+        }) => fields,
+        _ => return Ok(None),
+    };
+    let packed = is_packed(&input.attrs)?;
+    let values = struct_field_values(fields, packed);
+    let field_statements = fmt_field_statements(fields, values)?;
+    let non_exhaustive = field_statements.non_exhaustive();
+    let statements = &field_statements.statements;
+    Ok(Some(quote::quote! {
+        const NON_EXHAUSTIVE: bool = #non_exhaustive;
+
+        fn debug_fields(&self, __builder: &mut std::fmt::DebugStruct) {
+            #(#statements)*
+        }
+    }))
+}
+
+/// The body of the generated `Inspect::visit_fields` method, which passes each field to
+/// `__visit` formatted the same way as in the `Debug` output.
+fn inspect_body(input: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+            let packed = is_packed(&input.attrs)?;
+            let values = struct_field_values(fields, packed);
+            let statements = inspect_field_statements(fields, values)?;
+            Ok(quote::quote! {
+                #(#statements)*
+            })
+        }
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+            let mut arms = vec![];
+            for variant in variants {
+                let (pattern, values) = variant_pattern(variant);
+                let statements = inspect_field_statements(&variant.fields, values)?;
+                arms.push(quote::quote! {
+                    #pattern => {
+                        #(#statements)*
+                    }
+                });
+            }
+            Ok(match_self(&arms))
+        }
+        syn::Data::Union(_) => panic!("Expected struct or enum"),
+    }
+}
+
+/// One statement per field passing its name and formatted value to `__visit`.
+fn inspect_field_statements(
+    fields: &syn::Fields,
+    values: Vec<proc_macro2::TokenStream>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut statements = vec![];
+    for (i, (field, value)) in fields.iter().zip(values).enumerate() {
+        let attrs = parse_field_attrs(field)?;
+        if attrs.skip {
+            continue;
+        }
+        if attrs.flatten {
+            // The nested type has to be `#[debug(inspect)]` as well.
+            statements.push(skip_if(
+                &attrs,
+                &value,
+                quote::quote! { ::derive_debug::Inspect::visit_fields(#value, __visit); },
+            ));
+            continue;
+        }
+        let name = match (&attrs.rename, &field.ident) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(field_ident)) => format!("{}", field_ident),
+            (None, None) => i.to_string(),
+        };
+        let formatted = fmt_field_value(&attrs, value.clone());
+        statements.push(skip_if(
+            &attrs,
+            &value,
+            quote::quote! { __visit(#name, #formatted); },
+        ));
+    }
+    Ok(statements)
+}
+
+/// Every field of the struct, or of every variant of the enum.
+fn all_fields(data: &syn::Data) -> Box<dyn Iterator<Item = &syn::Field> + '_> {
+    match data {
+        syn::Data::Struct(syn::DataStruct { fields, .. }) => Box::new(fields.iter()),
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+            Box::new(variants.iter().flat_map(|variant| variant.fields.iter()))
+        }
+        syn::Data::Union(_) => panic!("Expected struct or enum"),
+    }
+}
+
+/// A match on `self` with the given arms, one per variant. Enums without variants match on
+/// `*self` instead, since a reference to an empty enum does not count as uninhabited.
+fn match_self(arms: &[proc_macro2::TokenStream]) -> proc_macro2::TokenStream {
+    if arms.is_empty() {
+        quote::quote! { match *self {} }
+    } else {
+        quote::quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    }
+}
+
+/// A match arm formatting one enum variant the way `#[derive(Debug)]` would.
+fn fmt_variant_arm(variant: &syn::Variant) -> syn::Result<proc_macro2::TokenStream> {
+    let quoted_variant_ident = format!("{}", variant.ident);
+    let (pattern, values) = variant_pattern(variant);
+    let body = fmt_fields(&quoted_variant_ident, &variant.fields, values)?;
+    Ok(quote::quote! {
+        #pattern => #body,
+    })
+}
+
+/// A pattern matching the variant and binding each of its fields, and the bindings in order.
+fn variant_pattern(
+    variant: &syn::Variant,
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    let variant_ident = &variant.ident;
+    // Generated names, so that fields called `fmt` or `__builder` don't shadow the locals of the
+    // generated code.
+    let bindings: Vec<_> = (0..variant.fields.len())
+        .map(|i| quote::format_ident!("__self_{}", i))
+        .collect();
+    let pattern = match &variant.fields {
+        syn::Fields::Named(_) => {
+            let field_idents = variant.fields.iter().map(|field| &field.ident);
+            quote::quote! { Self::#variant_ident { #(#field_idents: #bindings,)* } }
+        }
+        syn::Fields::Unnamed(_) => quote::quote! { Self::#variant_ident(#(#bindings,)*) },
+        syn::Fields::Unit => quote::quote! { Self::#variant_ident },
+    };
+    let values = bindings
+        .iter()
+        .map(|binding| quote::quote! { #binding })
+        .collect();
+    (pattern, values)
+}
+
+/// Formats a struct or variant called `name` with `debug_struct`, `debug_tuple` or just its name,
+/// depending on the shape of its fields. `values` holds a reference to each field, in order.
+fn fmt_fields(
+    name: &str,
+    fields: &syn::Fields,
+    values: Vec<proc_macro2::TokenStream>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let field_statements = fmt_field_statements(fields, values)?;
+    let statements = &field_statements.statements;
+    // Make it visible that some of the data was left out. Flattened fields only know whether
+    // they leave anything out once their type is known.
+    let finish = if field_statements.any_skipped {
+        quote::quote! { __builder.finish_non_exhaustive() }
+    } else if field_statements.flattened.is_empty() {
+        quote::quote! { __builder.finish() }
+    } else {
+        let non_exhaustive = field_statements.non_exhaustive();
+        quote::quote! {
+            if #non_exhaustive {
+                __builder.finish_non_exhaustive()
+            } else {
+                __builder.finish()
+            }
+        }
+    };
+
+    Ok(match fields {
+        syn::Fields::Named(_) => quote::quote! {{
+            let __builder = &mut fmt.debug_struct(#name);
+            #(#statements)*
+            #finish
+        }},
+        syn::Fields::Unnamed(_) => quote::quote! {{
+            let __builder = &mut fmt.debug_tuple(#name);
+            #(#statements)*
+            #finish
+        }},
+        syn::Fields::Unit => quote::quote! {
+            fmt.write_str(#name)
+        },
+    })
+}
+
+/// The statements adding each field to the `&mut` debug builder `__builder`.
+struct FieldStatements<'a> {
+    statements: Vec<proc_macro2::TokenStream>,
+    /// Whether any field is left out with `#[debug(skip)]`.
+    any_skipped: bool,
+    /// The types of the `#[debug(flatten)]` fields, which may leave out fields of their own.
+    flattened: Vec<&'a syn::Type>,
+}
+
+impl FieldStatements<'_> {
+    /// A constant `bool` expression telling whether the output leaves out any fields.
+    fn non_exhaustive(&self) -> proc_macro2::TokenStream {
+        let any_skipped = self.any_skipped;
+        let flattened = &self.flattened;
+        quote::quote! {
+            #any_skipped #(|| <#flattened as ::derive_debug::__private::DebugFields>::NON_EXHAUSTIVE)*
+        }
+    }
+}
+
+fn fmt_field_statements(
+    fields: &syn::Fields,
+    values: Vec<proc_macro2::TokenStream>,
+) -> syn::Result<FieldStatements<'_>> {
+    let mut field_statements = FieldStatements {
+        statements: vec![],
+        any_skipped: false,
+        flattened: vec![],
+    };
+    let statements = &mut field_statements.statements;
+    for (field, value) in fields.iter().zip(values) {
+        let attrs = parse_field_attrs(field)?;
+        if attrs.skip {
+            field_statements.any_skipped = true;
+            continue;
+        }
+        if attrs.flatten {
+            if field.ident.is_none() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`debug(flatten)` is only supported on named fields",
+                ));
+            }
+            // The nested type's own CustomDebug derive implements this trait.
+            statements.push(skip_if(
+                &attrs,
+                &value,
+                quote::quote! {
+                    ::derive_debug::__private::DebugFields::debug_fields(#value, __builder);
+                },
+            ));
+            field_statements.flattened.push(&field.ty);
+            continue;
+        }
+        let formatted = fmt_field_value(&attrs, value.clone());
+        let statement = match &field.ident {
+            Some(field_ident) => {
+                let quoted_field_ident = match &attrs.rename {
+                    Some(rename) => rename.to_token_stream(),
+                    None => format!("{}", field_ident).to_token_stream(),
+                };
+                quote::quote! { __builder.field(#quoted_field_ident, #formatted); }
+            }
+            None => quote::quote! { __builder.field(#formatted); },
+        };
+        statements.push(skip_if(&attrs, &value, statement));
+    }
+    Ok(field_statements)
+}
+
+/// Guards a field's statement with its `#[debug(skip_if = "...")]` predicate, if it has one.
+fn skip_if(
+    attrs: &FieldAttrs,
+    value: &proc_macro2::TokenStream,
+    statement: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match &attrs.skip_if {
+        Some(predicate) => quote::quote! {
+            if !#predicate(#value) {
+                #statement
+            }
+        },
+        None => statement,
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    /// `#[debug = "..."]`: format string applied to the field.
+    format: Option<syn::LitStr>,
+    /// `#[debug(skip)]`: leave the field out of the output.
+    skip: bool,
+    /// `#[debug(skip_if = "...")]`: predicate on the field deciding at format time whether to
+    /// leave it out.
+    skip_if: Option<syn::Path>,
+    /// `#[debug(rename = "...")]`: name printed in place of the field's own.
+    rename: Option<syn::LitStr>,
+    /// `#[debug(with = "...")]`: function formatting the field in place of its `Debug` impl.
+    with: Option<syn::Path>,
+    /// `#[debug(redact)]` or `#[debug(redact = "partial")]`: hide the value of the field.
+    redact: Option<Redact>,
+    /// `#[debug(bound = "...")]`: predicates replacing the bounds inferred from this field.
+    bound: Option<Vec<syn::WherePredicate>>,
+    /// `#[debug(flatten)]`: inline the fields of a nested CustomDebug struct.
+    flatten: bool,
+    /// `#[debug(len)]`: print only the length of a collection.
+    len: bool,
+    /// `#[debug(truncate = N)]`: print only the first N elements of a collection.
+    truncate: Option<syn::LitInt>,
+    /// `#[debug(hex)]`: print a byte buffer as a hex string.
+    hex: bool,
+}
+
+#[derive(PartialEq)]
+enum Redact {
+    /// Print `"<redacted>"` in place of the value.
+    Full,
+    /// Print only the last 4 characters of the value's `Display` output.
+    Partial,
+}
+
+impl FieldAttrs {
+    /// Whether formatting the field goes through the `Debug` impl of its type.
+    fn requires_debug(&self) -> bool {
+        !self.skip && self.with.is_none() && self.redact.is_none() && !self.len && !self.hex
+    }
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut field_attrs = FieldAttrs::default();
+    // Only one attribute may decide how the value is formatted.
+    let mut formatter = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("debug"))
+    {
+        match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(literal),
+                        ..
+                    }),
+                ..
+            }) => {
+                set_formatter(&mut formatter, r#"#[debug = "..."]"#, attr)?;
+                field_attrs.format = Some(literal.clone());
+            }
+            syn::Meta::NameValue(syn::MetaNameValue { value, .. }) => {
+                return Err(syn::Error::new_spanned(value, "Expected literal string"));
+            }
+            syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    field_attrs.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip_if") {
+                    let literal: syn::LitStr = meta.value()?.parse()?;
+                    field_attrs.skip_if = Some(literal.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    if field.ident.is_none() {
+                        return Err(meta.error("`debug(rename)` is only supported on named fields"));
+                    }
+                    field_attrs.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    set_formatter(&mut formatter, r#"debug(with = "...")"#, &meta.path)?;
+                    let literal: syn::LitStr = meta.value()?.parse()?;
+                    field_attrs.with = Some(literal.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    set_formatter(&mut formatter, "debug(flatten)", &meta.path)?;
+                    field_attrs.flatten = true;
+                    Ok(())
+                } else if meta.path.is_ident("len") {
+                    set_formatter(&mut formatter, "debug(len)", &meta.path)?;
+                    field_attrs.len = true;
+                    Ok(())
+                } else if meta.path.is_ident("truncate") {
+                    set_formatter(&mut formatter, "debug(truncate = N)", &meta.path)?;
+                    let limit: syn::LitInt = meta.value()?.parse()?;
+                    limit.base10_parse::<usize>()?;
+                    field_attrs.truncate = Some(limit);
+                    Ok(())
+                } else if meta.path.is_ident("hex") {
+                    set_formatter(&mut formatter, "debug(hex)", &meta.path)?;
+                    field_attrs.hex = true;
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    let predicates = parse_bound(meta.value()?.parse()?)?;
+                    field_attrs
+                        .bound
+                        .get_or_insert_with(Vec::new)
+                        .extend(predicates);
+                    Ok(())
+                } else if meta.path.is_ident("redact") {
+                    set_formatter(&mut formatter, "debug(redact)", &meta.path)?;
+                    if meta.input.peek(syn::Token![=]) {
+                        let literal: syn::LitStr = meta.value()?.parse()?;
+                        if literal.value() != "partial" {
+                            return Err(syn::Error::new(
+                                literal.span(),
+                                r#"expected `debug(redact)` or `debug(redact = "partial")`"#,
+                            ));
+                        }
+                        field_attrs.redact = Some(Redact::Partial);
+                    } else {
+                        field_attrs.redact = Some(Redact::Full);
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        r#"expected `debug(skip)`, `debug(skip_if = "...")`, `debug(rename = "...")`, `debug(with = "...")`, `debug(redact)`, `debug(bound = "...")`, `debug(flatten)`, `debug(len)`, `debug(truncate = N)` or `debug(hex)`"#,
+                    ))
+                }
+            })?,
+            syn::Meta::Path(_) => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    r#"expected `#[debug = "..."]` or `#[debug(...)]`"#,
+                ));
+            }
+        }
+    }
+    Ok(field_attrs)
+}
+
+/// Records that `attr` decides how the field's value is formatted, rejecting it if another
+/// attribute already does, since only one of them could apply.
+fn set_formatter(
+    formatter: &mut Option<&'static str>,
+    attr: &'static str,
+    tokens: impl ToTokens,
+) -> syn::Result<()> {
+    match formatter {
+        Some(previous) => Err(syn::Error::new_spanned(
+            tokens,
+            format!("`{}` cannot be combined with `{}`", attr, previous),
+        )),
+        None => {
+            *formatter = Some(attr);
+            Ok(())
+        }
+    }
+}
+
+/// The value passed to the debug builder for a field, applying its redaction,
+/// `#[debug(with = "...")]` formatter, size-aware formatting or `#[debug = "..."]` format
+/// string if there is one.
+fn fmt_field_value(
+    attrs: &FieldAttrs,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if let Some(redact) = &attrs.redact {
+        match redact {
+            Redact::Full => quote::quote! { &"<redacted>" },
+            // Values of 4 characters or fewer are masked completely.
+            Redact::Partial => quote::quote! {
+                &{
+                    let value = std::string::ToString::to_string(#value);
+                    let chars: std::vec::Vec<char> = value.chars().collect();
+                    if chars.len() > 4 {
+                        format!("****{}", chars[chars.len() - 4..].iter().collect::<std::string::String>())
+                    } else {
+                        std::string::String::from("****")
+                    }
+                }
+            },
+        }
+    } else if let Some(with) = &attrs.with {
+        quote::quote! { &::derive_debug::__private::DebugWith(#value, #with) }
+    } else if attrs.len {
+        quote::quote! { &format_args!("len = {}", (#value).len()) }
+    } else if attrs.hex {
+        quote::quote! { &::derive_debug::__private::Hex(&(#value)[..]) }
+    } else if let Some(limit) = &attrs.truncate {
+        quote::quote! { &::derive_debug::__private::Truncate((#value).iter(), #limit) }
+    } else if let Some(str_literal) = &attrs.format {
+        let args = str_literal.value();
+        // why does format_args! work but format! doesn't?
+        quote::quote! { &format_args!(#args, #value) }
+    } else {
+        value
+    }
+}
+
+/// The body of the generated `Display::fmt`, and the bounds it needs.
+///
+/// Structs take their format from a `#[display("...")]` on the type, enums from one on each
+/// variant. Unit variants without one display as their name.
+fn display_fmt_body(
+    input: &syn::DeriveInput,
+) -> syn::Result<(proc_macro2::TokenStream, Vec<syn::WherePredicate>)> {
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect();
+    let mut predicates = vec![];
+
+    let fmt_body = match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+            let literal = parse_display_attr(&input.attrs)?.ok_or_else(|| {
+                syn::Error::new_spanned(&input.ident, r#"expected `#[display("...")]`"#)
+            })?;
+            let values = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match &field.ident {
+                    Some(field_ident) => quote::quote! { self.#field_ident },
+                    None => {
+                        let index = syn::Index::from(i);
+                        quote::quote! { self.#index }
+                    }
+                })
+                .collect();
+            display_write(&literal, fields, values, &type_params, &mut predicates)?
+        }
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => {
+            let mut arms = vec![];
+            for variant in variants {
+                let variant_ident = &variant.ident;
+                let literal = match parse_display_attr(&variant.attrs)? {
+                    Some(literal) => literal,
+                    None if variant.fields.is_empty() => {
+                        syn::LitStr::new(&variant_ident.to_string(), variant_ident.span())
+                    }
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            variant_ident,
+                            r#"expected `#[display("...")]` on variant with fields"#,
+                        ))
+                    }
+                };
+                let (pattern, values) = variant_pattern(variant);
+                let body = display_write(
+                    &literal,
+                    &variant.fields,
+                    values,
+                    &type_params,
+                    &mut predicates,
+                )?;
+                arms.push(quote::quote! {
+                    #[allow(unused_variables)]
+                    #pattern => #body,
+                });
+            }
+            match_self(&arms)
+        }
+        syn::Data::Union(_) => panic!("Expected struct or enum"),
+    };
+
+    Ok((fmt_body, predicates))
+}
+
+/// A `write!` of the format string, passing every named field it mentions as a named argument,
+/// tuple fields as positional arguments, and recording the formatting trait each of them needs.
+fn display_write(
+    literal: &syn::LitStr,
+    fields: &syn::Fields,
+    values: Vec<proc_macro2::TokenStream>,
+    type_params: &[&syn::Ident],
+    predicates: &mut Vec<syn::WherePredicate>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut args = vec![];
+    let mut passed = vec![];
+    let mut positional = 0;
+    for (arg, trait_path) in format_placeholders(literal)? {
+        let name = match arg {
+            FormatArg::Named(name) => name,
+            FormatArg::Positional(index) => {
+                if let syn::Fields::Unnamed(_) = fields {
+                    if let Some(field) = fields.iter().nth(index) {
+                        if let Some(trait_path) = &trait_path {
+                            predicates.extend(inferred_predicates(
+                                &field.ty,
+                                type_params,
+                                trait_path,
+                            ));
+                        }
+                        positional = positional.max(index + 1);
+                    }
+                }
+                continue;
+            }
+        };
+        let Some((field, value)) = fields
+            .iter()
+            .zip(&values)
+            .find(|(field, _)| field.ident.as_ref().is_some_and(|ident| *ident == name))
+        else {
+            // Not a field; leave it to format_args! to resolve or report.
+            continue;
+        };
+        if let Some(trait_path) = &trait_path {
+            predicates.extend(inferred_predicates(&field.ty, type_params, trait_path));
+        }
+        if !passed.contains(&name) {
+            let arg_ident = quote::format_ident!("{}", name);
+            args.push(quote::quote! { #arg_ident = #value });
+            passed.push(name);
+        }
+    }
+    // Only pass tuple fields up to the last one used, since format_args! rejects unused
+    // positional arguments.
+    let positional = &values[..positional];
+    Ok(quote::quote! {
+        write!(fmt, #literal, #(#positional,)* #(#args),*)
+    })
+}
+
+fn parse_display_attr(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::LitStr>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("display"))
+        .map(|attr| attr.parse_args())
+        .transpose()
+}
+
+/// An argument referenced by a placeholder of a format string.
+enum FormatArg {
+    /// `{name}`.
+    Named(String),
+    /// `{0}`, or the next implicit position for `{}`.
+    Positional(usize),
+}
+
+/// The arguments referenced by a format string, each with the formatting trait its format spec
+/// uses. Width and precision arguments (`width$`, `1$` and `.*`) come with no trait, since they are
+/// plain `usize`s.
+fn format_placeholders(
+    literal: &syn::LitStr,
+) -> syn::Result<Vec<(FormatArg, Option<proc_macro2::TokenStream>)>> {
+    let value = literal.value();
+    let mut placeholders = vec![];
+    let mut next_implicit = 0;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(syn::Error::new(
+                                literal.span(),
+                                "unclosed `{` in format string",
+                            ))
+                        }
+                    }
+                }
+                let (name, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                // A `.*` precision takes the next implicit argument, before the value does.
+                if spec.contains(".*") {
+                    placeholders.push((FormatArg::Positional(next_implicit), None));
+                    next_implicit += 1;
+                }
+                for (end, _) in spec.match_indices('$') {
+                    let count = &spec[..end];
+                    let start = count
+                        .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+                        .map_or(0, |i| i + 1);
+                    let count = &count[start..];
+                    if let Ok(index) = count.parse() {
+                        placeholders.push((FormatArg::Positional(index), None));
+                    } else if !count.is_empty() {
+                        placeholders.push((FormatArg::Named(count.to_owned()), None));
+                    }
+                }
+                let trait_path = match spec.chars().last() {
+                    Some('?') => quote::quote! { std::fmt::Debug },
+                    Some('x') => quote::quote! { std::fmt::LowerHex },
+                    Some('X') => quote::quote! { std::fmt::UpperHex },
+                    Some('o') => quote::quote! { std::fmt::Octal },
+                    Some('b') => quote::quote! { std::fmt::Binary },
+                    Some('e') => quote::quote! { std::fmt::LowerExp },
+                    Some('E') => quote::quote! { std::fmt::UpperExp },
+                    Some('p') => quote::quote! { std::fmt::Pointer },
+                    _ => quote::quote! { std::fmt::Display },
+                };
+                let name = name.trim();
+                let arg = if name.is_empty() {
+                    next_implicit += 1;
+                    FormatArg::Positional(next_implicit - 1)
+                } else if let Ok(index) = name.parse() {
+                    FormatArg::Positional(index)
+                } else {
+                    FormatArg::Named(name.to_owned())
+                };
+                placeholders.push((arg, Some(trait_path)));
+            }
+            _ => {}
+        }
+    }
+    Ok(placeholders)
+}
+
+/// Walks the type recursively and collects every part of it that mentions T and has to implement
+/// Debug for the whole type to implement Debug, without duplicates.
+/// * T, Box<Vec<T>>, &'a [T; N] and (T, u8) need T.
+/// * T::Value and <T as Trait>::Value need themselves, not T.
+/// * HashMap<T::Key, T> needs both T::Key and T.
+/// * PhantomData<T>, *const T, fn(T) and Box<dyn Fn(T)> need nothing, because their Debug impls
+///   do not depend on T or because no bound on T could make them Debug.
+fn contains_inner_t<'a>(ty: &'a syn::Type, t_ident: &syn::Ident, found: &mut Vec<&'a syn::Type>) {
+    match ty {
+        syn::Type::Path(syn::TypePath {
+            qself: Some(qself), ..
+        }) => {
+            let mut in_qself = vec![];
+            contains_inner_t(&qself.ty, t_ident, &mut in_qself);
+            if !in_qself.is_empty() {
+                push_unique(found, ty);
+            }
+        }
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            if path
+                .segments
+                .iter()
+                .any(|segment| segment.ident == "PhantomData")
+            {
+                return;
+            }
+
+            if path.leading_colon.is_none() && &path.segments[0].ident == t_ident {
+                push_unique(found, ty);
+                return;
+            }
+
+            for segment in &path.segments {
+                if let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+                    args,
+                    ..
+                }) = &segment.arguments
+                {
+                    for arg in args {
+                        match arg {
+                            syn::GenericArgument::Type(ty) => contains_inner_t(ty, t_ident, found),
+                            syn::GenericArgument::AssocType(syn::AssocType { ty, .. }) => {
+                                contains_inner_t(ty, t_ident, found)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                // Parenthesized arguments only appear on Fn traits, whose Debug-ness never depends
+                // on their argument types.
+            }
+        }
+        syn::Type::Reference(syn::TypeReference { elem, .. })
+        | syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. })
+        | syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => contains_inner_t(elem, t_ident, found),
+        syn::Type::Tuple(syn::TypeTuple { elems, .. }) => {
+            for elem in elems {
+                contains_inner_t(elem, t_ident, found);
+            }
+        }
+        // Raw pointers and fn pointers are Debug for any pointee or signature, and trait objects
+        // or opaque types can't be made Debug by bounding their parameters.
+        _ => {}
+    }
+}
+
+fn push_unique<'a>(found: &mut Vec<&'a syn::Type>, ty: &'a syn::Type) {
+    if !found.contains(&ty) {
+        found.push(ty);
+    }
+}
@@ -1,184 +1,122 @@
-use quote::ToTokens;
-use std::collections::HashSet;
-use syn::Token;
-
-#[proc_macro_derive(CustomDebug, attributes(debug))]
-pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut parsed = syn::parse_macro_input!(input as syn::DeriveInput);
-
-    let ident = &parsed.ident;
-    let fields = if let syn::Data::Struct(syn::DataStruct { ref fields, .. }) = parsed.data {
-        fields
-    } else {
-        panic!("Expected struct");
-    };
-
-    let fields = if let syn::Fields::Named(syn::FieldsNamed { named, .. }) = fields {
-        named
-    } else {
-        panic!("Expected named fields");
-    };
-    let fmt_fields = fields.iter().map(|field| {
-        let field_ident = field
-            .ident
-            .as_ref()
-            .expect("Expected identifier for named field");
-        let quoted_field_ident = format!("{}", field_ident).to_token_stream();
-
-        if let Some(attr) = field
-            .attrs
-            .iter()
-            .find(|attr| attr.path().is_ident("debug"))
-        {
-            let expr = &attr
-                .meta
-                .require_name_value()
-                .expect("Expected name-value pattern")
-                .value;
-            let str_literal = if let syn::Expr::Lit(syn::ExprLit {
-                lit: syn::Lit::Str(literal),
-                ..
-            }) = expr
-            {
-                literal
-            } else {
-                panic!("Expected literal string");
-            };
-            let args = str_literal.value();
-            // why does format_args! work but format! doesn't?
-            quote::quote! { .field(#quoted_field_ident, &format_args!(#args, &self.#field_ident))}
-        } else {
-            quote::quote! { .field(#quoted_field_ident, &self.#field_ident)}
-        }
-    });
-
-    let attr_meta = parsed.attrs.iter().find_map(|attr| {
-        if let syn::Meta::List(syn::MetaList { path, .. }) = &attr.meta {
-            if path.is_ident("debug") {
-                let parsed_meta: syn::MetaNameValue = attr.parse_args().expect("");
-                Some(parsed_meta)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    });
-
-    let bound_literal = if let Some(meta) = &attr_meta {
-        if let syn::Expr::Lit(syn::ExprLit {
-            lit: syn::Lit::Str(literal),
-            ..
-        }) = &meta.value
-        {
-            Some(literal)
-        } else {
-            unimplemented!()
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, so the derives live in the derive_debug-impl crate and
+// are re-exported here next to the Inspect trait they can implement.
+pub use derive_debug_impl::{CustomDebug, CustomDisplay};
+
+use std::fmt::Debug;
+
+/// Visits the fields of a value one at a time, as key/value pairs.
+///
+/// `#[derive(CustomDebug)]` implements this for types marked `#[debug(inspect)]`, honoring the
+/// same field attributes as the generated `Debug` impl.
+pub trait Inspect {
+    /// Calls `f` with the name and formatted value of each field, in declaration order.
+    ///
+    /// Tuple fields are named by their index. For enums, only the fields of the current variant
+    /// are visited.
+    fn visit_fields(&self, f: &mut dyn FnMut(&'static str, &dyn Debug));
+}
+
+// Not public API. Used by the code that the derive generates.
+#[doc(hidden)]
+pub mod __private {
+    use std::cell::Cell;
+    use std::fmt::{self, Debug, DebugStruct, Formatter};
+
+    /// Implemented by every struct with named fields that derives `CustomDebug`, so that other
+    /// types can `#[debug(flatten)]` it.
+    pub trait DebugFields {
+        /// Whether the fields leave anything out, including the fields of flattened fields.
+        const NON_EXHAUSTIVE: bool;
+
+        fn debug_fields(&self, builder: &mut DebugStruct);
+    }
+
+    /// Fields of `#[repr(packed)]` structs are copied out before formatting, since they may not
+    /// be aligned. Going through this trait reports non-`Copy` fields with a clear message.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` is not `Copy`, so it cannot be formatted as a field of a `#[repr(packed)]` struct",
+        label = "fields of packed structs are copied out before formatting",
+        note = "mark the field `#[debug(skip)]` to leave it out"
+    )]
+    pub trait PackedField: Copy {}
+
+    impl<T: Copy> PackedField for T {}
+
+    pub fn packed_field<T: PackedField>(value: T) -> T {
+        value
+    }
+
+    /// Formats a field with its `#[debug(with = "...")]` function.
+    pub struct DebugWith<'a, T: ?Sized>(pub &'a T, pub fn(&T, &mut Formatter) -> fmt::Result);
+
+    impl<T: ?Sized> Debug for DebugWith<'_, T> {
+        fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+            (self.1)(self.0, fmt)
         }
-    } else {
-        None
-    };
-
-    let mut associated_bounds = vec![];
-    parsed.generics.params.iter_mut().for_each(|ref mut param| {
-        let fields = if let syn::Data::Struct(syn::DataStruct { ref fields, .. }) = &parsed.data {
-            fields
-        } else {
-            panic!("expected struct fields");
-        };
-
-        if let syn::GenericParam::Type(syn::TypeParam {
-            ident: generic_ident,
-            bounds,
-            ..
-        }) = param
-        {
-            let mut bounds_set: HashSet<syn::TypeParamBound> = std::collections::HashSet::new();
-            if let Some(bound) = bound_literal {
-                let value: proc_macro2::TokenStream =
-                    bound.value().parse().expect("failed parsing to string");
-                associated_bounds.push(quote::quote! {#value});
-            } else {
-                for field in fields {
-                    let path = if let syn::Type::Path(syn::TypePath { path, .. }) = &field.ty {
-                        path
-                    } else {
-                        unimplemented!()
-                    };
-
-                    if let Some(segments) = contains_inner_t(&path.segments, generic_ident) {
-                        if segments.len() > 1 {
-                            associated_bounds.push(quote::quote! {#segments: std::fmt::Debug});
-                        } else {
-                            bounds_set.insert(syn::parse_quote!(std::fmt::Debug));
-                        }
-                    }
-                }
+    }
+
+    /// Formats a `#[debug(hex)]` byte buffer as a lowercase hex string.
+    pub struct Hex<'a>(pub &'a [u8]);
+
+    impl Debug for Hex<'_> {
+        fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+            for byte in self.0 {
+                write!(fmt, "{:02x}", byte)?;
             }
-            bounds.extend(bounds_set.into_iter())
-        } else {
-            unimplemented!();
+            Ok(())
         }
-    });
-
-    let (impl_generics, ty_generics, where_clause) = &mut parsed.generics.split_for_impl();
-    let clause = if where_clause.is_some() {
-        quote::quote! {, #(#associated_bounds,)*}
-    } else {
-        quote::quote! {where #(#associated_bounds,)*}
-    };
-
-    let quoted_struct_ident = format!("{}", ident);
-    let res = quote::quote! {
-        impl #impl_generics std::fmt::Debug for #ident #ty_generics #where_clause #clause {
-            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-                fmt.debug_struct(#quoted_struct_ident)
-                #(#fmt_fields)*
-                .finish()
+    }
+
+    /// Formats the first elements of a `#[debug(truncate = N)]` collection. Iterators over
+    /// borrowed collections are `Clone`, which lets `fmt` walk them through a shared reference.
+    pub struct Truncate<I>(pub I, pub usize);
+
+    impl<I> Debug for Truncate<I>
+    where
+        I: Iterator + Clone,
+        I::Item: Debug,
+    {
+        fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+            let mut iter = self.0.clone();
+            let mut list = fmt.debug_list();
+            list.entries(iter.by_ref().take(self.1));
+            let more = iter.count();
+            if more > 0 {
+                list.entry(&format_args!("\u{2026} ({} more)", more));
             }
+            list.finish()
         }
-    };
+    }
 
-    proc_macro::TokenStream::from(res)
-}
+    thread_local! {
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
 
-/// Walks the type recursively and returns either when it finds some reference to T or the end.
-/// * T contains T.
-/// * Box<Vec<T>> contains T.
-/// * NonNull<Vec<HashMap<K, A>>> does not contain T.
-/// * Vec<Phantom<Box<Vec<T>>>> does not contain T.
-fn contains_inner_t<'a>(
-    segments: &'a syn::punctuated::Punctuated<syn::PathSegment, Token!(::)>,
-    t_ident: &syn::Ident,
-) -> Option<&'a syn::punctuated::Punctuated<syn::PathSegment, Token!(::)>> {
-    if segments
-        .iter()
-        .any(|segment| segment.ident == "PhantomData")
-    {
-        return None;
+    /// Counts one level of nesting of `#[debug(max_depth = N)]` values on this thread, for as long
+    /// as it is alive.
+    pub struct DepthGuard {
+        depth: usize,
     }
 
-    if &segments[0].ident == t_ident {
-        return Some(segments);
+    impl DepthGuard {
+        pub fn enter() -> Self {
+            let depth = DEPTH.with(|cell| {
+                let depth = cell.get() + 1;
+                cell.set(depth);
+                depth
+            });
+            DepthGuard { depth }
+        }
+
+        pub fn exceeds(&self, max_depth: usize) -> bool {
+            self.depth > max_depth
+        }
     }
 
-    let res = segments
-        .iter()
-        .find_map(|segment| match &segment.arguments {
-            syn::PathArguments::None => None,
-            syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
-                args,
-                ..
-            }) => args.iter().find_map(|arg| {
-                if let syn::GenericArgument::Type(syn::Type::Path(syn::TypePath { path, .. })) = arg
-                {
-                    contains_inner_t(&path.segments, t_ident)
-                } else {
-                    None
-                }
-            }),
-            _ => unimplemented!(),
-        });
-
-    res
+    impl Drop for DepthGuard {
+        fn drop(&mut self) {
+            DEPTH.with(|cell| cell.set(cell.get() - 1));
+        }
+    }
 }
@@ -0,0 +1,67 @@
+// CustomDebug should work on enums too, formatting each variant the way
+// #[derive(Debug)] would:
+//
+//   - unit variants print just their name,
+//   - tuple variants go through Formatter::debug_tuple,
+//   - struct variants go through Formatter::debug_struct.
+//
+// Per-field #[debug = "..."] format strings and bound inference apply to
+// variant fields exactly as they do to struct fields.
+//
+// Like #[derive(Debug)], the derive accepts enums without any variants, and
+// variant fields whose names clash with the generated code, such as `fmt`.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub enum Instruction<T> {
+    Nop,
+    Load(#[debug = "{:#x}"] u16, T),
+    Store {
+        #[debug = "0b{:08b}"]
+        mask: u8,
+        value: T,
+    },
+}
+
+#[derive(CustomDebug)]
+pub enum Tagged<T> {
+    Empty,
+    Marker(PhantomData<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+#[derive(CustomDebug)]
+pub enum Spec {
+    Width { fmt: u8, __builder: u8 },
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let nop: Instruction<u8> = Instruction::Nop;
+    assert_eq!(format!("{:?}", nop), "Nop");
+
+    let load = Instruction::Load(0xff, "a");
+    assert_eq!(format!("{:?}", load), r#"Load(0xff, "a")"#);
+
+    let store = Instruction::Store {
+        mask: 0b0000_0101,
+        value: 7,
+    };
+    assert_eq!(format!("{:?}", store), "Store { mask: 0b00000101, value: 7 }");
+
+    struct NotDebug;
+    assert_debug::<Tagged<NotDebug>>();
+    assert_debug::<Never>();
+
+    let spec = Spec::Width {
+        fmt: 1,
+        __builder: 2,
+    };
+    assert_eq!(format!("{:?}", spec), "Width { fmt: 1, __builder: 2 }");
+}
@@ -0,0 +1,24 @@
+// Newtypes and marker types are common struct shapes. Tuple structs should be
+// formatted with Formatter::debug_tuple, still honoring per-field
+// #[debug = "..."] format strings, and unit structs should print just their
+// name, the same as #[derive(Debug)].
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Meters(f64);
+
+#[derive(CustomDebug)]
+pub struct Color(#[debug = "{:#x}"] u32, &'static str);
+
+#[derive(CustomDebug)]
+pub struct Marker;
+
+fn main() {
+    assert_eq!(format!("{:?}", Meters(1.5)), "Meters(1.5)");
+    assert_eq!(
+        format!("{:?}", Color(0xff8800, "orange")),
+        r#"Color(0xff8800, "orange")"#,
+    );
+    assert_eq!(format!("{:?}", Marker), "Marker");
+}
@@ -0,0 +1,49 @@
+// Some fields are not interesting in debug output, or hold handles that do not
+// implement Debug at all. A field-level #[debug(skip)] leaves the field out of
+// the generated output entirely.
+//
+// When any field is skipped, finish the debug builder with
+// finish_non_exhaustive() rather than finish(), which prints `..` at the end so
+// readers know data is hidden.
+//
+// Skipped fields must not contribute to the inferred `T: Debug` bounds, so a
+// non-Debug type parameter used only in skipped fields does not need Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub struct Handle;
+
+#[derive(CustomDebug)]
+pub struct Connection<H> {
+    peer: String,
+    #[debug(skip)]
+    handle: H,
+    retries: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(u8, #[debug(skip)] Handle);
+
+#[derive(CustomDebug)]
+pub struct Plain {
+    value: u8,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let connection = Connection {
+        peer: "localhost".to_owned(),
+        handle: Handle,
+        retries: 3,
+    };
+    assert_eq!(
+        format!("{:?}", connection),
+        r#"Connection { peer: "localhost", retries: 3, .. }"#,
+    );
+    assert_eq!(format!("{:?}", Pair(1, Handle)), "Pair(1, ..)");
+    assert_eq!(format!("{:?}", Plain { value: 1 }), "Plain { value: 1 }");
+
+    assert_debug::<Connection<Handle>>();
+}
@@ -0,0 +1,53 @@
+// Format strings only cover what format_args! can express. For anything else,
+// a field-level #[debug(with = "path")] names a function that formats the
+// field instead of its Debug impl:
+//
+//     fn fmt_duration(value: &Duration, fmt: &mut Formatter) -> fmt::Result
+//
+// The generated code calls it through a small wrapper type implementing Debug.
+// A field formatted this way adds no Debug bound on its type, so it also works
+// for type parameters that do not implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::time::Duration;
+
+mod formatters {
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn fmt_duration(value: &Duration, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}ms", value.as_millis())
+    }
+}
+
+fn fmt_opaque<T>(_: &T, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("<opaque>")
+}
+
+#[derive(CustomDebug)]
+pub struct Request<B> {
+    path: String,
+    #[debug(with = "formatters::fmt_duration")]
+    elapsed: Duration,
+    #[debug(with = "fmt_opaque")]
+    body: B,
+}
+
+struct NotDebug;
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let request = Request {
+        path: "/".to_owned(),
+        elapsed: Duration::from_millis(1500),
+        body: NotDebug,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { path: "/", elapsed: 1500ms, body: <opaque> }"#,
+    );
+
+    assert_debug::<Request<NotDebug>>();
+}
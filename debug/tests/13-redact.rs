@@ -0,0 +1,40 @@
+// Request and credential structs get logged when something goes wrong, so
+// their secrets must not end up in the debug output.
+//
+// A field-level #[debug(redact)] prints "<redacted>" in place of the value.
+// #[debug(redact = "partial")] prints only the last 4 characters of the
+// value's Display output, which is usually enough to tell two tokens apart.
+// Values of 4 characters or fewer are masked completely.
+//
+// Fully redacted fields need no Debug impl. Partially redacted fields need a
+// Display impl instead, which is added to the where-clause of the generated
+// impl.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Credentials<S> {
+    username: String,
+    #[debug(redact)]
+    password: S,
+    #[debug(redact = "partial")]
+    api_token: String,
+    #[debug(redact = "partial")]
+    pin: u16,
+}
+
+struct Secret;
+
+fn main() {
+    let credentials = Credentials {
+        username: "admin".to_owned(),
+        password: Secret,
+        api_token: "sk_live_0123456789abcd".to_owned(),
+        pin: 1234,
+    };
+    let debug = format!("{:?}", credentials);
+    assert_eq!(
+        debug,
+        r#"Credentials { username: "admin", password: "<redacted>", api_token: "****abcd", pin: "****" }"#,
+    );
+}
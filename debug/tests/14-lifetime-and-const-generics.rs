@@ -0,0 +1,42 @@
+// Structs with lifetime parameters and const generic parameters should derive
+// CustomDebug too. Those parameters are carried over to the generated impl
+// unchanged by split_for_impl; only type parameters get inferred Debug bounds.
+//
+//     impl<'a, T, const N: usize> Debug for Ring<'a, T, N>
+//     where
+//         T: Debug,
+//     {...}
+
+use derive_debug::CustomDebug;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Ring<'a, T, const N: usize> {
+    name: Cow<'a, str>,
+    items: Vec<T>,
+    #[debug = "{:#x}"]
+    mask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Window<'a, const N: usize> {
+    title: Cow<'a, str>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let ring: Ring<u8, 4> = Ring {
+        name: Cow::Borrowed("ring"),
+        items: vec![1, 2],
+        mask: 0xf,
+    };
+    assert_eq!(
+        format!("{:?}", ring),
+        r#"Ring { name: "ring", items: [1, 2], mask: 0xf }"#,
+    );
+
+    assert_debug::<Ring<'static, u8, 8>>();
+    assert_debug::<Window<'static, 3>>();
+}
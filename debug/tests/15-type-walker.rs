@@ -0,0 +1,70 @@
+// Bound inference has to look inside every kind of type, not only paths:
+// references, arrays, slices, tuples, trait objects, function pointers and
+// qualified paths. The generated impl should carry exactly the bounds needed,
+// no more:
+//
+//     impl<'a, T, U, I, const N: usize> Debug for Everything<'a, T, U, I, N>
+//     where
+//         T: Debug,
+//         U: Debug,
+//         <I as Iterator>::Item: Debug,
+//     {...}
+//
+// Function pointers and raw pointers are Debug whatever they point to, and a
+// boxed closure is never Debug, so a type parameter that only appears in those
+// positions must not be required to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+#[derive(CustomDebug)]
+pub struct Everything<'a, T, U, I: Iterator, const N: usize> {
+    reference: &'a T,
+    array: [U; N],
+    tuple: (&'a [T], Option<U>),
+    item: Option<<I as Iterator>::Item>,
+}
+
+#[derive(CustomDebug)]
+pub struct Callbacks<A, B> {
+    pointer: fn(A) -> B,
+    raw: *const A,
+    #[debug(with = "fmt_callback")]
+    callback: Box<dyn Fn(B)>,
+}
+
+fn fmt_callback<T: ?Sized>(_: &T, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("<callback>")
+}
+
+struct NotDebug;
+
+struct Numbers;
+
+impl Iterator for Numbers {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        None
+    }
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let value = 1u8;
+    let everything: Everything<u8, char, Numbers, 2> = Everything {
+        reference: &value,
+        array: ['a', 'b'],
+        tuple: (&[2, 3], None),
+        item: Some(4),
+    };
+    assert_eq!(
+        format!("{:?}", everything),
+        "Everything { reference: 1, array: ['a', 'b'], tuple: ([2, 3], None), item: Some(4) }",
+    );
+
+    // `Numbers` itself is not Debug; only its Item is.
+    assert_debug::<Everything<'static, u8, u8, Numbers, 1>>();
+    assert_debug::<Callbacks<NotDebug, NotDebug>>();
+}
@@ -0,0 +1,77 @@
+// A field-level #[debug(bound = "...")] replaces the bounds inferred from that
+// one field, while bounds inferred from the other fields are kept:
+//
+//     #[derive(CustomDebug)]
+//     pub struct Wrapper<T: Trait, U> {
+//         #[debug(bound = "T::Value: Debug")]
+//         field: Field<T>,
+//         normal: U,
+//     }
+//
+//     impl<T: Trait, U> Debug for Wrapper<T, U>
+//     where
+//         T::Value: Debug,
+//         U: Debug,
+//     {...}
+//
+// All predicates, whether written in the original where-clause, given in
+// attributes or inferred, end up in a single where-clause: `Wrapper` below
+// already has a where-clause of its own, and a second one appended after it
+// would not parse.
+// The container-level bound attribute is recognized wherever it appears among
+// the attributes and is applied once, however many type parameters there are.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U>
+where
+    U: Debug,
+{
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+    again: Option<U>,
+}
+
+#[derive(CustomDebug)]
+struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+#[derive(CustomDebug)]
+#[allow(dead_code)]
+#[debug(bound = "A: Debug, B: Debug")]
+pub struct Pair<A, B, C> {
+    first: A,
+    second: B,
+    marker: std::marker::PhantomData<C>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Wrapper<Id, u8>>();
+    assert_debug::<Pair<u8, u8, Id>>();
+
+    let wrapper: Wrapper<Id, u8> = Wrapper {
+        field: Field { values: vec![1] },
+        normal: 2,
+        again: None,
+    };
+    assert_eq!(
+        format!("{:?}", wrapper),
+        "Wrapper { field: Field { values: [1] }, normal: 2, again: None }",
+    );
+}
@@ -0,0 +1,128 @@
+// Many types need a human-readable Display next to their CustomDebug output.
+// The CustomDisplay derive takes a format string from a #[display("...")]
+// attribute on the struct, or on each enum variant, in which named
+// placeholders refer to fields:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{name} ({id:#x})")]
+//     pub struct User {
+//         name: String,
+//         id: u32,
+//     }
+//
+// Tuple fields are passed as positional arguments, so `{0}` (or just `{}`)
+// refers to the first one:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{0}m")]
+//     pub struct Meters(f64);
+//
+// Fields can also give the width or precision of another placeholder, as in
+// `{name:>width$}` or `{:.*}`.
+//
+// Unit variants without an attribute display as their name.
+//
+// Bounds are inferred the same way as for CustomDebug, using the formatting
+// trait each placeholder asks for: Display for `{name}`, LowerHex for
+// `{id:#x}`, Debug for `{value:?}`, Pointer for `{0:p}` and so on. Type
+// parameters only used in fields that the format string does not mention, or
+// only as a width or precision, get no bound.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::Display;
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{name} ({id:#x})")]
+pub struct User<I> {
+    name: String,
+    id: I,
+}
+
+#[derive(CustomDisplay)]
+pub enum Event<T, U> {
+    Started,
+    #[display("progress {percent}%")]
+    Progress { percent: u8, detail: U },
+    #[display("done: {value:?}")]
+    Done { value: T },
+    #[display("failed")]
+    Failed(U),
+}
+
+#[derive(CustomDisplay)]
+pub enum Attempt<T, U> {
+    #[display("retry {} of {1:?}")]
+    Retry(T, U),
+}
+
+#[derive(CustomDisplay)]
+pub enum Spec {
+    #[display("w{fmt}")]
+    Width { fmt: u8 },
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}m")]
+pub struct Meters(f64);
+
+#[derive(CustomDisplay)]
+#[display("{0:x}")]
+pub struct Hex<T>(T, T);
+
+#[derive(CustomDisplay)]
+#[display("[{name:>width$}]")]
+pub struct Padded {
+    name: String,
+    width: usize,
+}
+
+#[derive(CustomDisplay)]
+#[display("{:.*}")]
+pub struct Rounded(usize, f64);
+
+#[derive(CustomDisplay)]
+#[display("{0:p}")]
+pub struct Address<T>(T);
+
+#[derive(CustomDisplay)]
+pub enum Never {}
+
+struct NotDisplay;
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    let user = User {
+        name: "ferris".to_owned(),
+        id: 255u32,
+    };
+    assert_eq!(user.to_string(), "ferris (0xff)");
+    assert_eq!(format!("{:?}", user), r#"User { name: "ferris", id: 255 }"#);
+
+    assert_display::<Never>();
+
+    assert_eq!(Event::<u8, NotDisplay>::Started.to_string(), "Started");
+    let progress: Event<u8, NotDisplay> = Event::Progress {
+        percent: 50,
+        detail: NotDisplay,
+    };
+    assert_eq!(progress.to_string(), "progress 50%");
+    let done: Event<Option<u8>, NotDisplay> = Event::Done { value: Some(1) };
+    assert_eq!(done.to_string(), "done: Some(1)");
+
+    assert_display::<Event<u8, NotDisplay>>();
+
+    assert_eq!(Spec::Width { fmt: 8 }.to_string(), "w8");
+    assert_eq!(Meters(1.5).to_string(), "1.5m");
+    assert_eq!(Hex(255, 0).to_string(), "ff");
+    let padded = Padded {
+        name: "ab".to_owned(),
+        width: 4,
+    };
+    assert_eq!(padded.to_string(), "[  ab]");
+    assert_eq!(Rounded(2, 1.2345).to_string(), "1.23");
+    assert_display::<Address<*const u8>>();
+    assert!(Address(&0u8 as *const u8).to_string().starts_with("0x"));
+    let retry: Attempt<u8, Option<u8>> = Attempt::Retry(2, None);
+    assert_eq!(retry.to_string(), "retry 2 of None");
+}
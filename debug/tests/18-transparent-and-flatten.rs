@@ -0,0 +1,110 @@
+// Newtype IDs read better as their bare value, and layered config structs read
+// better without one level of nesting per layer.
+//
+// A container-level #[debug(transparent)] formats a struct as its one field
+// alone, without the struct name. Any format string on that field still
+// applies. Skipped fields do not count, so the struct needs exactly one field
+// that is not skipped.
+//
+// A field-level #[debug(flatten)] inlines the fields of a nested struct into
+// the parent's debug_struct output. The nested type has to derive CustomDebug
+// as well; the derive gives every struct with named fields an impl of a hidden
+// trait that the parent calls for this. If the nested struct skips some of its
+// fields, the parent's output ends in `..` just as if it had skipped them.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId {
+    inner: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Mask(#[debug = "0b{:04b}"] u8);
+
+#[derive(CustomDebug)]
+pub struct Network {
+    host: String,
+    port: u16,
+}
+
+#[derive(CustomDebug)]
+pub struct Config<T> {
+    id: UserId,
+    #[debug(flatten)]
+    network: Network,
+    extra: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: String,
+    #[debug(skip)]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+pub struct Login {
+    #[debug(flatten)]
+    credentials: Credentials,
+}
+
+#[derive(CustomDebug)]
+pub struct Session {
+    #[debug(flatten)]
+    login: Login,
+    id: u32,
+}
+
+#[derive(CustomDebug)]
+pub enum Layer {
+    Base {
+        #[debug(flatten)]
+        network: Network,
+    },
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", UserId { inner: 42 }), "42");
+    assert_eq!(format!("{:?}", Mask(5)), "0b0101");
+
+    let network = Network {
+        host: "localhost".to_owned(),
+        port: 8080,
+    };
+    let config = Config {
+        id: UserId { inner: 7 },
+        network,
+        extra: true,
+    };
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Config { id: 7, host: "localhost", port: 8080, extra: true }"#,
+    );
+
+    let login = Login {
+        credentials: Credentials {
+            user: "ferris".to_owned(),
+            password: "hunter2".to_owned(),
+        },
+    };
+    assert_eq!(format!("{:?}", login), r#"Login { user: "ferris", .. }"#);
+    let session = Session { login, id: 1 };
+    assert_eq!(
+        format!("{:?}", session),
+        r#"Session { user: "ferris", id: 1, .. }"#,
+    );
+
+    let layer = Layer::Base {
+        network: Network {
+            host: "example.com".to_owned(),
+            port: 443,
+        },
+    };
+    assert_eq!(
+        format!("{:?}", layer),
+        r#"Base { host: "example.com", port: 443 }"#,
+    );
+}
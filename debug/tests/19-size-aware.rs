@@ -0,0 +1,69 @@
+// Large collections and byte buffers make for unreadable logs when printed
+// element by element.
+//
+// A field-level #[debug(len)] prints only `len = N`, calling the field's
+// `len` method. #[debug(truncate = N)] prints the first N elements of anything
+// with an `iter` method, such as vectors, arrays, slices, sets and maps,
+// followed by `… (M more)` when some were left out. #[debug(hex)] prints a
+// byte buffer (anything that can be sliced into `[u8]`) as one compact
+// lowercase hex string. Both work on borrowed buffers too.
+//
+// Neither `len` nor `hex` goes through the field's Debug impl, so they add no
+// Debug bound for the field's type.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Packet<'a> {
+    #[debug(hex)]
+    header: [u8; 4],
+    #[debug(len)]
+    payload: Vec<u8>,
+    #[debug(truncate = 3)]
+    hops: Vec<u32>,
+    #[debug(truncate = 2)]
+    tags: BTreeSet<&'static str>,
+    #[debug(truncate = 1)]
+    window: &'a [u16],
+    #[debug(hex)]
+    trailer: &'a [u8],
+}
+
+pub struct NotDebug;
+
+#[derive(CustomDebug)]
+pub struct Batch<T> {
+    #[debug(len)]
+    items: Vec<T>,
+    #[debug(hex)]
+    checksum: Vec<u8>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let packet = Packet {
+        header: [0x0a, 0x1b, 0xff, 0x00],
+        payload: vec![0; 1024],
+        hops: vec![1, 2, 3, 4, 5],
+        tags: ["a", "b"].into_iter().collect(),
+        window: &[7, 8, 9],
+        trailer: &[0xc0, 0xff, 0xee],
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        r#"Packet { header: 0a1bff00, payload: len = 1024, hops: [1, 2, 3, … (2 more)], tags: ["a", "b"], window: [7, … (2 more)], trailer: c0ffee }"#,
+    );
+
+    assert_debug::<Batch<NotDebug>>();
+    let batch = Batch {
+        items: vec![NotDebug, NotDebug],
+        checksum: vec![0xde, 0xad],
+    };
+    assert_eq!(
+        format!("{:?}", batch),
+        "Batch { items: len = 2, checksum: dead }",
+    );
+}
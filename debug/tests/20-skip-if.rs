@@ -0,0 +1,60 @@
+// Structs with many optional fields print mostly `None`s.
+//
+// A field-level #[debug(skip_if = "...")] names a function taking a reference
+// to the field and returning bool, the way serde's skip_serializing_if does.
+// It is called each time the value is formatted, and the field is left out of
+// the output whenever it returns true. Unlike #[debug(skip)], the output does
+// not end in `..` when a field is omitted this way.
+
+use derive_debug::CustomDebug;
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+#[derive(CustomDebug)]
+pub struct Options {
+    name: String,
+    #[debug(skip_if = "Option::is_none")]
+    timeout: Option<u32>,
+    #[debug(skip_if = "Vec::is_empty")]
+    headers: Vec<String>,
+    #[debug(skip_if = "is_zero")]
+    #[debug = "{}s"]
+    retries: u32,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Click {
+        #[debug(skip_if = "Option::is_none")]
+        button: Option<u8>,
+    },
+}
+
+fn main() {
+    let options = Options {
+        name: "default".to_owned(),
+        timeout: None,
+        headers: vec![],
+        retries: 0,
+    };
+    assert_eq!(format!("{:?}", options), r#"Options { name: "default" }"#);
+
+    let options = Options {
+        name: "custom".to_owned(),
+        timeout: Some(30),
+        headers: vec!["Accept".to_owned()],
+        retries: 3,
+    };
+    assert_eq!(
+        format!("{:?}", options),
+        r#"Options { name: "custom", timeout: Some(30), headers: ["Accept"], retries: 3s }"#,
+    );
+
+    assert_eq!(format!("{:?}", Event::Click { button: None }), "Click");
+    assert_eq!(
+        format!("{:?}", Event::Click { button: Some(1) }),
+        "Click { button: Some(1) }",
+    );
+}
@@ -0,0 +1,47 @@
+// Log lines read better with short names, and field names that show up in
+// logs should not change whenever the code is refactored.
+//
+// A container-level #[debug(name = "...")] replaces the struct name in the
+// output, and a field-level #[debug(rename = "...")] replaces the field name.
+// Enums print their variant names, so the container attribute is only
+// accepted on structs, and tuple fields have no name to replace.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(name = "Req")]
+pub struct HttpRequest {
+    #[debug(rename = "id")]
+    request_identifier: u64,
+    method: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(name = "Pt")]
+pub struct Point(i32, i32);
+
+#[derive(CustomDebug)]
+pub enum Message {
+    Quit {
+        #[debug(rename = "code")]
+        exit_code: i32,
+    },
+}
+
+fn main() {
+    let request = HttpRequest {
+        request_identifier: 7,
+        method: "GET",
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Req { id: 7, method: "GET" }"#,
+    );
+
+    assert_eq!(format!("{:?}", Point(1, 2)), "Pt(1, 2)");
+
+    assert_eq!(
+        format!("{:?}", Message::Quit { exit_code: 1 }),
+        "Quit { code: 1 }",
+    );
+}
@@ -0,0 +1,37 @@
+// Wire-format headers are often #[repr(packed)], and taking a reference to a
+// field of a packed struct is an error because the field may be unaligned.
+//
+// For packed structs the derive copies each field into a temporary before
+// formatting it, the way the standard library's derive does. That requires
+// every formatted field to be Copy; skipped fields do not need to be.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct Header {
+    tag: u8,
+    #[debug = "0x{:08x}"]
+    length: u32,
+    #[debug(hex)]
+    checksum: [u8; 2],
+}
+
+#[derive(CustomDebug)]
+#[repr(packed(2))]
+pub struct Frame(u8, u64, #[debug(skip)] String);
+
+fn main() {
+    let header = Header {
+        tag: 1,
+        length: 512,
+        checksum: [0xbe, 0xef],
+    };
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { tag: 1, length: 0x00000200, checksum: beef }",
+    );
+
+    let frame = Frame(2, 3, String::from("payload"));
+    assert_eq!(format!("{:?}", frame), "Frame(2, 3, ..)");
+}
@@ -0,0 +1,13 @@
+// A field of a packed struct that is not Copy cannot be copied out to be
+// formatted. The error points at the field's type.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Message {
+    id: u32,
+    body: String,
+}
+
+fn main() {}
@@ -0,0 +1,96 @@
+// Structured loggers want key/value pairs rather than one preformatted string.
+//
+// A container-level #[debug(inspect)] makes the derive also implement the
+// derive_debug::Inspect trait, whose visit_fields method calls a closure with
+// the name of each field and a `&dyn Debug` for its value. The value is
+// formatted exactly as in the Debug output, so skip, skip_if, rename, format
+// strings, `with` functions and redaction all apply. Tuple fields are named by
+// their index, and a flattened field has to be #[debug(inspect)] too.
+//
+// The trait cannot live in the same crate as the derive, because crates with
+// the "proc-macro" crate type may only export procedural macros. Like the
+// bitfield project, derive_debug is a regular crate that defines the trait and
+// re-exports the derive from a derive_debug-impl crate.
+
+use derive_debug::{CustomDebug, Inspect};
+
+#[derive(CustomDebug)]
+#[debug(inspect)]
+pub struct Connection {
+    #[debug(rename = "peer")]
+    peer_address: String,
+    #[debug = "{}ms"]
+    latency: u32,
+    #[debug(redact)]
+    token: String,
+    #[debug(skip)]
+    buffer: Vec<u8>,
+    #[debug(skip_if = "Option::is_none")]
+    user: Option<String>,
+}
+
+#[derive(CustomDebug)]
+#[debug(inspect)]
+pub struct Request<T> {
+    method: &'static str,
+    #[debug(flatten)]
+    connection: Connection,
+    body: T,
+}
+
+#[derive(CustomDebug)]
+#[debug(inspect)]
+pub enum Shape {
+    Circle(f64),
+    Rectangle { width: u32, height: u32 },
+}
+
+#[derive(CustomDebug)]
+#[debug(inspect)]
+pub enum Never {}
+
+fn pairs(value: &dyn Inspect) -> Vec<String> {
+    let mut pairs = vec![];
+    value.visit_fields(&mut |name, value| pairs.push(format!("{}={:?}", name, value)));
+    pairs
+}
+
+fn assert_inspect<T: Inspect>() {}
+
+fn main() {
+    assert_inspect::<Never>();
+
+    let connection = Connection {
+        peer_address: "10.0.0.1".to_owned(),
+        latency: 12,
+        token: "hunter2".to_owned(),
+        buffer: vec![0; 64],
+        user: None,
+    };
+    assert_eq!(
+        pairs(&connection),
+        [r#"peer="10.0.0.1""#, "latency=12ms", r#"token="<redacted>""#],
+    );
+
+    let request = Request {
+        method: "GET",
+        connection,
+        body: [1, 2],
+    };
+    assert_eq!(
+        pairs(&request),
+        [
+            r#"method="GET""#,
+            r#"peer="10.0.0.1""#,
+            "latency=12ms",
+            r#"token="<redacted>""#,
+            "body=[1, 2]",
+        ],
+    );
+
+    assert_eq!(pairs(&Shape::Circle(1.5)), ["0=1.5"]);
+    assert_eq!(
+        pairs(&Shape::Rectangle { width: 3, height: 4 }),
+        ["width=3", "height=4"],
+    );
+}
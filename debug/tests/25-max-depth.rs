@@ -0,0 +1,78 @@
+// Printing a large tree produces unreadable output, and a cyclic Rc graph
+// recurses until the stack overflows.
+//
+// A container-level #[debug(max_depth = N)] counts how deeply values are
+// nested while they are being formatted, and prints `…` in place of any value
+// more than N levels deep. The count is kept per thread and shared by every
+// type that has a limit, so mutually recursive types such as the nodes of an
+// AST limit each other.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub struct Tree {
+    value: u32,
+    children: Vec<Tree>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub enum Expr {
+    Literal(i64),
+    Block(Box<Stmt>),
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub struct Stmt {
+    expr: Expr,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub struct Node {
+    id: u32,
+    next: Option<Rc<RefCell<Node>>>,
+}
+
+fn main() {
+    let leaf = |value| Tree {
+        value,
+        children: vec![],
+    };
+    let tree = Tree {
+        value: 1,
+        children: vec![Tree {
+            value: 2,
+            children: vec![leaf(3)],
+        }],
+    };
+    assert_eq!(
+        format!("{:?}", tree),
+        "Tree { value: 1, children: [Tree { value: 2, children: […] }] }",
+    );
+
+    let expr = Expr::Block(Box::new(Stmt {
+        expr: Expr::Block(Box::new(Stmt {
+            expr: Expr::Literal(1),
+        })),
+    }));
+    assert_eq!(
+        format!("{:?}", expr),
+        "Block(Stmt { expr: Block(…) })",
+    );
+
+    // A cycle: formatting it would never end without the limit.
+    let node = Rc::new(RefCell::new(Node { id: 1, next: None }));
+    node.borrow_mut().next = Some(Rc::clone(&node));
+    let output = format!("{:?}", node.borrow());
+    node.borrow_mut().next = None;
+    assert_eq!(
+        output,
+        "Node { id: 1, next: Some(RefCell { value: Node { id: 1, next: Some(RefCell { \
+         value: Node { id: 1, next: Some(RefCell { value: … }) } }) } }) }",
+    );
+}
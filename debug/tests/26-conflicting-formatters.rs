@@ -0,0 +1,20 @@
+// Only one attribute can decide how a field's value is formatted: a format
+// string, `with`, `redact`, `len`, `truncate`, `hex` or `flatten`. Combining
+// two of them is an error rather than silently ignoring one.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(len, hex)]
+    payload: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Account {
+    #[debug = "{:?}"]
+    #[debug(redact)]
+    password: String,
+}
+
+fn main() {}
@@ -9,4 +9,22 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-tuple-and-unit-struct.rs");
+    t.pass("tests/11-skip.rs");
+    t.pass("tests/12-with.rs");
+    t.pass("tests/13-redact.rs");
+    t.pass("tests/14-lifetime-and-const-generics.rs");
+    t.pass("tests/15-type-walker.rs");
+    t.pass("tests/16-field-bound.rs");
+    t.pass("tests/17-display.rs");
+    t.pass("tests/18-transparent-and-flatten.rs");
+    t.pass("tests/19-size-aware.rs");
+    t.pass("tests/20-skip-if.rs");
+    t.pass("tests/21-rename.rs");
+    t.pass("tests/22-packed.rs");
+    t.compile_fail("tests/23-packed-not-copy.rs");
+    t.pass("tests/24-inspect.rs");
+    t.pass("tests/25-max-depth.rs");
+    t.compile_fail("tests/26-conflicting-formatters.rs");
 }